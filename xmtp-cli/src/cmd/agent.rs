@@ -6,26 +6,16 @@
 use std::io::{self, Write};
 
 use serde_json::{Value, json};
+use xmtp::stream::{ClientEvent, EventKind, EventSelection};
 use xmtp::{
     ConsentState, ConversationOrderBy, ConversationType, CreateGroupOptions, DeliveryStatus,
     ListConversationsOptions, ListMessagesOptions, MessageKind, Recipient, SendOptions,
-    SortDirection, stream,
+    SortDirection,
 };
 
 use super::config;
 use crate::decode;
 
-enum StreamEvent {
-    Message {
-        msg_id: String,
-    },
-    Conversation {
-        id: String,
-        conv_type: Option<ConversationType>,
-        name: Option<String>,
-    },
-}
-
 /// Write a JSON value as a single line to stdout, flushing immediately.
 fn emit(value: &Value) {
     let stdout = io::stdout();
@@ -91,19 +81,21 @@ pub(crate) fn conversations(profile: &str, consent: Option<&str>, json: bool) ->
         order_by: ConversationOrderBy::LastActivity,
         ..Default::default()
     };
-    let convs = client.list_conversations(&opts)?;
+    let convs = client.list_conversation_items(&opts)?;
 
     if json {
         let items: Vec<Value> = convs
             .iter()
-            .map(|c| {
-                let last = c.last_message().ok().flatten();
+            .map(|item| {
+                let c = &item.conversation;
+                let last = item.last_message.as_ref();
                 json!({
                     "id": c.id(),
                     "type": conv_type_str(c.conversation_type()),
                     "name": c.name(),
-                    "last_message": last.as_ref().map(decode::text),
-                    "last_message_ns": last.as_ref().map(|m| m.sent_at_ns),
+                    "last_message": last.map(decode::preview_text),
+                    "last_message_ns": last.map(|m| m.sent_at_ns),
+                    "commit_log_forked": item.commit_log_forked,
                 })
             })
             .collect();
@@ -113,7 +105,8 @@ pub(crate) fn conversations(profile: &str, consent: Option<&str>, json: bool) ->
             println!("No conversations.");
             return Ok(());
         }
-        for c in &convs {
+        for item in &convs {
+            let c = &item.conversation;
             let kind = conv_type_str(c.conversation_type());
             let name = c.name().unwrap_or_default();
             let id = c.id();
@@ -354,99 +347,51 @@ pub(crate) fn request(profile: &str, conv_id: &str, action: &str, json: bool) ->
 ///
 /// Outputs NDJSON events to stdout. Runs until interrupted.
 pub(crate) fn stream_events(profile: &str, kind: &str) -> xmtp::Result<()> {
+    let selection = match kind {
+        "messages" => EventSelection::new().with(EventKind::Message),
+        "conversations" => EventSelection::new().with(EventKind::Conversation),
+        "all" => EventSelection::new()
+            .with(EventKind::Message)
+            .with(EventKind::Conversation),
+        _ => {
+            return Err(xmtp::XmtpError::InvalidArgument(format!(
+                "invalid stream type: {kind} (expected: messages, conversations, all)"
+            )));
+        }
+    };
+
     let (_, client) = config::open_client(profile)?;
     drop(client.sync_welcomes());
     drop(client.sync_all(&[]));
 
-    let (tx, rx) = std::sync::mpsc::channel::<StreamEvent>();
-    let stream_msgs = kind == "messages" || kind == "all";
-    let stream_convs = kind == "conversations" || kind == "all";
-
-    if !stream_msgs && !stream_convs {
-        return Err(xmtp::XmtpError::InvalidArgument(format!(
-            "invalid stream type: {kind} (expected: messages, conversations, all)"
-        )));
-    }
-
-    if stream_msgs {
-        let sub = stream::messages(&client, None, &[])?;
-        let tx_msg = tx.clone();
-        std::thread::spawn(move || pipe_messages(sub, &tx_msg));
-    }
-
-    if stream_convs {
-        let sub = stream::conversations(&client, None)?;
-        let tx_conv = tx.clone();
-        std::thread::spawn(move || pipe_conversations(sub, &tx_conv));
-    }
-
-    drop(tx);
-
+    let sub = client.events(&selection)?;
     emit(&json!({"type": "ready", "stream": kind}));
 
-    while let Ok(event) = rx.recv() {
+    for event in sub {
         match event {
-            StreamEvent::Message { msg_id } => {
-                if let Ok(Some(msg)) = client.message_by_id(&msg_id) {
-                    emit(&json!({
-                        "type": "message",
-                        "message_id": msg.id,
-                        "conversation_id": msg.conversation_id,
-                        "sender_inbox_id": msg.sender_inbox_id,
-                        "sent_at_ns": msg.sent_at_ns,
-                        "delivery_status": delivery_status_str(msg.delivery_status),
-                        "content": decode::content_json(&msg),
-                    }));
-                }
-            }
-            StreamEvent::Conversation {
-                id,
-                conv_type,
-                name,
-            } => {
+            ClientEvent::Message(msg) => emit(&json!({
+                "type": "message",
+                "message_id": msg.id,
+                "conversation_id": msg.conversation_id,
+                "sender_inbox_id": msg.sender_inbox_id,
+                "sent_at_ns": msg.sent_at_ns,
+                "delivery_status": delivery_status_str(msg.delivery_status),
+                "content": decode::content_json(&msg),
+            })),
+            ClientEvent::Conversation(conv) => {
                 drop(client.sync_welcomes());
                 emit(&json!({
                     "type": "conversation",
-                    "conversation_id": id,
-                    "conversation_type": conv_type_str(conv_type),
-                    "name": name,
+                    "conversation_id": conv.id(),
+                    "conversation_type": conv_type_str(conv.conversation_type()),
+                    "name": conv.name(),
                 }));
             }
+            // Not selected above.
+            ClientEvent::Consent(_)
+            | ClientEvent::Preferences(_)
+            | ClientEvent::MessageDeleted(_) => {}
         }
     }
     Ok(())
 }
-
-fn pipe_messages(
-    sub: stream::Subscription<stream::MessageEvent>,
-    tx: &std::sync::mpsc::Sender<StreamEvent>,
-) {
-    for ev in sub {
-        if tx
-            .send(StreamEvent::Message {
-                msg_id: ev.message_id,
-            })
-            .is_err()
-        {
-            break;
-        }
-    }
-}
-
-fn pipe_conversations(
-    sub: stream::Subscription<xmtp::Conversation>,
-    tx: &std::sync::mpsc::Sender<StreamEvent>,
-) {
-    for conv in sub {
-        if tx
-            .send(StreamEvent::Conversation {
-                id: conv.id(),
-                conv_type: conv.conversation_type(),
-                name: conv.name(),
-            })
-            .is_err()
-        {
-            break;
-        }
-    }
-}
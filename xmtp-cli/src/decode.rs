@@ -5,22 +5,78 @@
 //! this module instead of duplicating decode logic.
 
 use serde_json::{Value, json};
-use xmtp::content::Content;
-use xmtp::{DeliveryStatus, Message, MessageKind};
+use xmtp::content::{Actions, Content, GroupUpdated, Reply, TransactionReference, WalletSendCalls};
+use xmtp::{DeliveryStatus, Message, MessageKind, MessagePreview};
 
 // ── Text extraction ───────────────────────────────────────────────
 
-/// Extract text from an inner [`EncodedContent`](xmtp::content::EncodedContent)
-/// (e.g. a reply body).
-pub(crate) fn reply_text(ec: &xmtp::content::EncodedContent) -> String {
-    let is_text = ec
-        .r#type
-        .as_ref()
-        .is_some_and(|t| t.type_id == "text" || t.type_id == "markdown");
-    if is_text {
-        String::from_utf8(ec.content.clone()).unwrap_or_default()
-    } else {
-        ec.fallback.clone().unwrap_or_else(|| "[reply]".into())
+/// Extract text from a reply body, falling back to its fallback text.
+pub(crate) fn reply_text(reply: &Reply) -> String {
+    match reply.inner() {
+        Ok(Content::Text(s) | Content::Markdown(s)) => s,
+        _ => reply
+            .content
+            .fallback
+            .clone()
+            .unwrap_or_else(|| "[reply]".into()),
+    }
+}
+
+/// One-line summary of a transaction reference (e.g. `[tx: 1.5 USDC]`).
+fn tx_summary(tx: &TransactionReference) -> String {
+    tx.metadata.as_ref().map_or_else(
+        || format!("[tx: {}]", truncate_id(&tx.reference, 13)),
+        |m| {
+            let amount = m.amount / 10f64.powi(i32::try_from(m.decimals).unwrap_or(0));
+            format!("[tx: {amount} {}]", m.currency)
+        },
+    )
+}
+
+/// Describe a group change, e.g. `alice added bob; group_name: "X" → "Y"`.
+fn group_summary(g: &GroupUpdated) -> String {
+    let actor = truncate_id(&g.initiated_by, 13);
+    let names = |ids: &[String]| {
+        ids.iter()
+            .map(|id| truncate_id(id, 13))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut parts = Vec::new();
+    if !g.added_inboxes.is_empty() {
+        parts.push(format!("{actor} added {}", names(&g.added_inboxes)));
+    }
+    if !g.removed_inboxes.is_empty() {
+        parts.push(format!("{actor} removed {}", names(&g.removed_inboxes)));
+    }
+    if !g.left_inboxes.is_empty() {
+        parts.push(format!("{} left", names(&g.left_inboxes)));
+    }
+    parts.extend(g.metadata_changes.iter().map(|c| {
+        format!(
+            "{actor} changed {} from {:?} to {:?}",
+            c.field,
+            c.old_value.as_deref().unwrap_or_default(),
+            c.new_value.as_deref().unwrap_or_default()
+        )
+    }));
+    parts.join("; ")
+}
+
+/// Prompt plus button labels, e.g. `Pick one [Yes | No]`.
+fn actions_summary(actions: &Actions) -> String {
+    let labels: Vec<&str> = actions.actions.iter().map(|a| a.label.as_str()).collect();
+    format!("{} [{}]", actions.description, labels.join(" | "))
+}
+
+/// One-line summary of a wallet call request.
+fn calls_summary(calls: &WalletSendCalls) -> String {
+    match calls.calls.as_slice() {
+        [call] => call.metadata.as_ref().map_or_else(
+            || "[tx request]".into(),
+            |m| format!("[tx request: {}]", m.description),
+        ),
+        many => format!("[tx request: {} calls]", many.len()),
     }
 }
 
@@ -54,11 +110,23 @@ pub(crate) fn preview(msg: &Message) -> String {
     if msg.kind != MessageKind::Application {
         return String::new();
     }
-    match msg.decode() {
+    preview_content(msg.decode(), msg.fallback.as_deref())
+}
+
+/// Decode a conversation's last-message preview to a short sidebar string.
+pub(crate) fn last_preview(msg: &MessagePreview) -> String {
+    if msg.kind != Some(MessageKind::Application) {
+        return String::new();
+    }
+    preview_content(msg.decode(), msg.fallback.as_deref())
+}
+
+fn preview_content(decoded: xmtp::Result<Content>, fallback: Option<&str>) -> String {
+    match decoded {
         Ok(Content::Text(s) | Content::Markdown(s)) => truncate(&s, 28),
         Ok(Content::Reaction(r)) => truncate(&r.content, 28),
         Ok(Content::ReadReceipt) => String::new(),
-        Ok(Content::Reply(r)) => truncate(&reply_text(&r.content), 28),
+        Ok(Content::Reply(r)) => truncate(&reply_text(&r), 28),
         Ok(Content::Attachment(a)) => {
             format!(
                 "[file: {}]",
@@ -66,7 +134,14 @@ pub(crate) fn preview(msg: &Message) -> String {
             )
         }
         Ok(Content::RemoteAttachment(_)) => "[attachment]".into(),
-        Ok(Content::Unknown { .. }) | Err(_) => msg.fallback.clone().unwrap_or_default(),
+        Ok(Content::MultiRemoteAttachment(m)) => format!("[{} attachments]", m.attachments.len()),
+        Ok(Content::TransactionReference(t)) => tx_summary(&t),
+        Ok(Content::GroupUpdated(g)) => group_summary(&g),
+        Ok(Content::WalletSendCalls(w)) => calls_summary(&w),
+        Ok(Content::Actions(a)) => actions_summary(&a),
+        Ok(Content::Intent(i)) => format!("[selected: {}]", i.action_id),
+        // Unknown, custom, and any newer content types.
+        Ok(_) | Err(_) => fallback.unwrap_or_default().to_owned(),
     }
 }
 
@@ -76,13 +151,21 @@ pub(crate) fn body(msg: &Message) -> String {
         Ok(Content::Text(s) | Content::Markdown(s)) => s,
         Ok(Content::Reaction(r)) => format!("[{}]", r.content),
         Ok(Content::ReadReceipt) => "[read]".into(),
-        Ok(Content::Reply(r)) => reply_text(&r.content),
+        Ok(Content::Reply(r)) => reply_text(&r),
         Ok(Content::Attachment(a)) => {
             format!("[file: {}]", a.filename.as_deref().unwrap_or("file"))
         }
         Ok(Content::RemoteAttachment(_)) => "[remote attachment]".into(),
+        Ok(Content::MultiRemoteAttachment(m)) => {
+            format!("[{} remote attachments]", m.attachments.len())
+        }
+        Ok(Content::TransactionReference(t)) => tx_summary(&t),
+        Ok(Content::GroupUpdated(g)) => group_summary(&g),
+        Ok(Content::WalletSendCalls(w)) => calls_summary(&w),
+        Ok(Content::Actions(a)) => actions_summary(&a),
+        Ok(Content::Intent(i)) => format!("[selected: {}]", i.action_id),
         Ok(Content::Unknown { content_type, .. }) => format!("[unknown: {content_type}]"),
-        Err(_) => msg.fallback.clone().unwrap_or_default(),
+        Ok(_) | Err(_) => msg.fallback.clone().unwrap_or_default(),
     }
 }
 
@@ -102,20 +185,33 @@ pub(crate) fn text(msg: &Message) -> String {
     if msg.kind != MessageKind::Application {
         return String::new();
     }
-    match msg.decode() {
+    text_content(msg.decode())
+}
+
+/// Plain text extraction from a conversation's last-message preview.
+pub(crate) fn preview_text(msg: &MessagePreview) -> String {
+    if msg.kind != Some(MessageKind::Application) {
+        return String::new();
+    }
+    text_content(msg.decode())
+}
+
+fn text_content(decoded: xmtp::Result<Content>) -> String {
+    match decoded {
         Ok(Content::Text(s) | Content::Markdown(s)) => s,
         Ok(Content::Reaction(r)) => r.content,
-        Ok(Content::Reply(r)) => reply_text(&r.content),
+        Ok(Content::Reply(r)) => reply_text(&r),
         _ => String::new(),
     }
 }
 
 /// Build a JSON representation of message content.
 pub(crate) fn content_json(msg: &Message) -> Value {
-    if msg.kind != MessageKind::Application {
+    let decoded = msg.decode();
+    if msg.kind != MessageKind::Application && !matches!(decoded, Ok(Content::GroupUpdated(_))) {
         return json!({"type": "system"});
     }
-    match msg.decode() {
+    match decoded {
         Ok(Content::Text(s)) => json!({"type": "text", "text": s}),
         Ok(Content::Markdown(s)) => json!({"type": "markdown", "text": s}),
         Ok(Content::Reaction(r)) => json!({
@@ -126,7 +222,7 @@ pub(crate) fn content_json(msg: &Message) -> Value {
         Ok(Content::Reply(r)) => json!({
             "type": "reply",
             "reference_message_id": r.reference,
-            "text": reply_text(&r.content),
+            "text": reply_text(&r),
         }),
         Ok(Content::ReadReceipt) => json!({"type": "read_receipt"}),
         Ok(Content::Attachment(a)) => json!({
@@ -140,10 +236,66 @@ pub(crate) fn content_json(msg: &Message) -> Value {
             "url": a.url,
             "filename": a.filename,
         }),
+        Ok(Content::MultiRemoteAttachment(m)) => json!({
+            "type": "multi_remote_attachment",
+            "attachments": m.attachments.iter().map(|a| json!({
+                "url": a.url,
+                "filename": a.filename,
+            })).collect::<Vec<_>>(),
+        }),
+        Ok(Content::TransactionReference(t)) => json!({
+            "type": "transaction_reference",
+            "network_id": t.network_id,
+            "reference": t.reference,
+            "currency": t.metadata.as_ref().map(|m| &m.currency),
+            "amount": t.metadata.as_ref().map(|m| m.amount),
+            "decimals": t.metadata.as_ref().map(|m| m.decimals),
+        }),
+        Ok(Content::WalletSendCalls(w)) => json!({
+            "type": "wallet_send_calls",
+            "chain_id": w.chain_id,
+            "from": w.from,
+            "calls": w.calls.iter().map(|c| json!({
+                "to": c.to,
+                "value": c.value,
+                "description": c.metadata.as_ref().map(|m| &m.description),
+            })).collect::<Vec<_>>(),
+        }),
+        Ok(Content::Actions(a)) => json!({
+            "type": "actions",
+            "id": a.id,
+            "description": a.description,
+            "actions": a.actions.iter().map(|b| json!({
+                "id": b.id,
+                "label": b.label,
+            })).collect::<Vec<_>>(),
+        }),
+        Ok(Content::Intent(i)) => json!({
+            "type": "intent",
+            "id": i.id,
+            "action_id": i.action_id,
+        }),
+        Ok(Content::GroupUpdated(g)) => json!({
+            "type": "group_updated",
+            "initiated_by": g.initiated_by,
+            "added_inboxes": g.added_inboxes,
+            "removed_inboxes": g.removed_inboxes,
+            "left_inboxes": g.left_inboxes,
+            "metadata_changes": g.metadata_changes.iter().map(|c| json!({
+                "field": c.field,
+                "old": c.old_value,
+                "new": c.new_value,
+            })).collect::<Vec<_>>(),
+        }),
         Ok(Content::Unknown { content_type, .. }) => json!({
             "type": "unknown",
             "content_type": content_type,
         }),
+        Ok(Content::Custom(c)) => json!({
+            "type": "custom",
+            "content_type": c.content_type.to_string(),
+        }),
+        Ok(_) => json!({"type": "unsupported", "fallback": msg.fallback}),
         Err(e) => json!({"type": "error", "error": e.to_string()}),
     }
 }
@@ -145,7 +145,7 @@ pub(crate) enum Cmd {
         metadata_field: Option<MetadataField>,
     },
     /// Stream: incoming message.
-    StreamMsg { msg_id: String, conv_id: String },
+    StreamMsg(Box<Message>),
     /// Stream: new or updated conversation.
     StreamConv,
     /// Background ENS resolver: address resolved to optional name.
@@ -112,7 +112,7 @@ fn start_ens_resolver(rpc_url: &str, cmd_tx: &CmdTx) -> Option<mpsc::Sender<Stri
     /// Each subscription is consumed in a dedicated thread that forwards events
     /// to `cmd_tx`. Threads exit naturally when the sender breaks (app exit).
     fn start_streams(&self, cmd_tx: &CmdTx) {
-        match stream::messages(&self.client, None, &[]) {
+        match stream::enriched_messages(&self.client, None, &[]) {
             Ok(sub) => {
                 let tx = cmd_tx.clone();
                 std::thread::spawn(move || forward_messages(sub, &tx));
@@ -147,7 +147,7 @@ fn dispatch(&mut self, cmd: Cmd) {
             Cmd::AddMember(input) => self.add_member(&input),
             Cmd::RemoveMember(id) => self.remove_member(&id),
             Cmd::ToggleAdmin(id) => self.toggle_admin(&id),
-            Cmd::StreamMsg { msg_id, conv_id } => self.on_stream_msg(&msg_id, conv_id),
+            Cmd::StreamMsg(msg) => self.on_stream_msg(&msg),
             Cmd::StreamConv => {
                 drop(self.client.sync_welcomes());
                 self.send_conversations();
@@ -363,23 +363,22 @@ fn toggle_admin(&mut self, inbox_id: &str) {
         }
     }
 
-    fn on_stream_msg(&mut self, msg_id: &str, conv_id: String) {
-        let is_active = self.active.as_ref().is_some_and(|(id, _)| *id == conv_id);
+    fn on_stream_msg(&mut self, msg: &Message) {
+        let conv_id = &msg.conversation_id;
+        let is_active = self.active.as_ref().is_some_and(|(id, _)| id == conv_id);
         if is_active {
             let Some((id, conv)) = self.active.take() else {
                 return;
             };
-            self.send_msgs(&conv_id, &conv);
+            self.send_msgs(conv_id, &conv);
             self.active = Some((id, conv));
         }
-        if let Ok(Some(msg)) = self.client.message_by_id(msg_id) {
-            drop(self.tx.send(Event::Preview {
-                conv_id,
-                text: decode::preview(&msg),
-                time_ns: msg.sent_at_ns,
-                unread: !is_active,
-            }));
-        }
+        drop(self.tx.send(Event::Preview {
+            conv_id: conv_id.clone(),
+            text: decode::preview(msg),
+            time_ns: msg.sent_at_ns,
+            unread: !is_active,
+        }));
     }
 
     fn flash(&self, msg: &str) {
@@ -518,10 +517,14 @@ fn build_conv_list(&mut self, consent: &[ConsentState]) -> Vec<ConvEntry> {
             order_by: ConversationOrderBy::LastActivity,
             ..Default::default()
         };
-        let convs = self.client.list_conversations(&opts).unwrap_or_default();
-        convs
+        let items = self
+            .client
+            .list_conversation_items(&opts)
+            .unwrap_or_default();
+        items
             .iter()
-            .map(|conv| {
+            .map(|item| {
+                let conv = &item.conversation;
                 let id = conv.id();
                 let is_group = conv.conversation_type() == Some(ConversationType::Group);
                 let label = if is_group {
@@ -530,9 +533,11 @@ fn build_conv_list(&mut self, consent: &[ConsentState]) -> Vec<ConvEntry> {
                 } else {
                     self.dm_peer_label(conv)
                 };
-                let last = conv.last_message().ok().flatten();
-                let preview = last.as_ref().map_or(String::new(), decode::preview);
-                let last_ns = last.as_ref().map_or(0, |m| m.sent_at_ns);
+                let preview = item
+                    .last_message
+                    .as_ref()
+                    .map_or(String::new(), decode::last_preview);
+                let last_ns = item.last_message_sent_at_ns;
                 ConvEntry {
                     id,
                     label,
@@ -658,15 +663,9 @@ fn ens_resolver_loop(resolver: &EnsResolver, rx: &mpsc::Receiver<String>, cmd: &
     }
 }
 
-fn forward_messages(sub: stream::Subscription<stream::MessageEvent>, tx: &CmdTx) {
-    for ev in sub {
-        if tx
-            .send(Cmd::StreamMsg {
-                msg_id: ev.message_id,
-                conv_id: ev.conversation_id,
-            })
-            .is_err()
-        {
+fn forward_messages(sub: stream::Subscription<Message>, tx: &CmdTx) {
+    for msg in sub {
+        if tx.send(Cmd::StreamMsg(Box::new(msg))).is_err() {
             break;
         }
     }
@@ -0,0 +1,399 @@
+//! Async facade over [`Client`] and [`Conversation`] for tokio applications.
+//!
+//! Enabled via the `async` Cargo feature:
+//!
+//! ```toml
+//! [dependencies]
+//! xmtp = { version = "0.1", features = ["async"] }
+//! ```
+//!
+//! Every FFI call blocks until libxmtp finishes. Called from a tokio worker,
+//! the FFI runs the call under `block_in_place`, which ties up that worker for
+//! the whole call and panics on a current-thread runtime. These wrappers move
+//! each call onto [`tokio::task::spawn_blocking`] instead and return `Send`
+//! futures, making them usable directly from axum handlers and spawned tasks.
+//!
+//! Methods not mirrored here remain reachable via [`AsyncClient::client`] and
+//! [`AsyncConversation::conversation`]; call them inside `spawn_blocking`.
+
+use std::sync::Arc;
+
+use crate::client::{Client, ClientBuilder};
+use crate::conversation::{Conversation, GroupMember, Message, ProcessedMessage};
+use crate::error::{Result, XmtpError};
+use crate::resolve::Recipient;
+use crate::types::{
+    AccountIdentifier, ConsentState, CreateGroupOptions, InboxState, ListConversationsOptions,
+    ListMessagesOptions, SendOptions, Signer, SyncResult,
+};
+
+/// Run `f` on tokio's blocking pool, propagating panics to the caller.
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(XmtpError::Ffi(format!("blocking task: {e}"))),
+    }
+}
+
+/// A cheaply cloneable async handle to a [`Client`].
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    inner: Arc<Client>,
+}
+
+impl From<Client> for AsyncClient {
+    fn from(client: Client) -> Self {
+        Self::new(client)
+    }
+}
+
+impl AsyncClient {
+    /// Wrap an existing client.
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            inner: Arc::new(client),
+        }
+    }
+
+    /// Build (and register, if needed) a client without blocking the executor.
+    pub async fn build(builder: ClientBuilder, signer: Arc<dyn Signer>) -> Result<Self> {
+        blocking(move || builder.build(signer.as_ref()))
+            .await
+            .map(Self::new)
+    }
+
+    /// The underlying sync client.
+    #[must_use]
+    pub fn client(&self) -> &Client {
+        &self.inner
+    }
+
+    /// Run a closure against the sync client on the blocking pool.
+    pub async fn with<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Client) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        blocking(move || f(&inner)).await
+    }
+
+    /// The inbox ID for this client.
+    pub fn inbox_id(&self) -> Result<String> {
+        self.inner.inbox_id()
+    }
+
+    /// See [`Client::sync_all`].
+    pub async fn sync_all(&self, consent_states: &[ConsentState]) -> Result<SyncResult> {
+        let states = consent_states.to_vec();
+        self.with(move |c| c.sync_all(&states)).await
+    }
+
+    /// See [`Client::sync_welcomes`].
+    pub async fn sync_welcomes(&self) -> Result<()> {
+        self.with(Client::sync_welcomes).await
+    }
+
+    /// See [`Client::group`].
+    pub async fn group(
+        &self,
+        members: &[Recipient],
+        opts: &CreateGroupOptions,
+    ) -> Result<AsyncConversation> {
+        let (members, opts) = (members.to_vec(), opts.clone());
+        self.with(move |c| c.group(&members, &opts))
+            .await
+            .map(AsyncConversation::new)
+    }
+
+    /// See [`Client::dm`].
+    pub async fn dm(&self, to: &Recipient) -> Result<AsyncConversation> {
+        let to = to.clone();
+        self.with(move |c| c.dm(&to))
+            .await
+            .map(AsyncConversation::new)
+    }
+
+    /// See [`Client::conversation`].
+    pub async fn conversation(&self, hex_id: &str) -> Result<Option<AsyncConversation>> {
+        let id = hex_id.to_owned();
+        let conv = self.with(move |c| c.conversation(&id)).await?;
+        Ok(conv.map(AsyncConversation::new))
+    }
+
+    /// See [`Client::list_conversations`].
+    pub async fn list_conversations(
+        &self,
+        options: &ListConversationsOptions,
+    ) -> Result<Vec<AsyncConversation>> {
+        let options = options.clone();
+        let convs = self.with(move |c| c.list_conversations(&options)).await?;
+        Ok(convs.into_iter().map(AsyncConversation::new).collect())
+    }
+
+    /// See [`Client::message_by_id`].
+    pub async fn message_by_id(&self, message_id_hex: &str) -> Result<Option<Message>> {
+        let id = message_id_hex.to_owned();
+        self.with(move |c| c.message_by_id(&id)).await
+    }
+
+    /// See [`Client::can_message`].
+    pub async fn can_message(&self, identifiers: &[AccountIdentifier]) -> Result<Vec<bool>> {
+        let identifiers = identifiers.to_vec();
+        self.with(move |c| c.can_message(&identifiers)).await
+    }
+
+    /// See [`Client::inbox_state`].
+    pub async fn inbox_state(&self, refresh: bool) -> Result<Vec<InboxState>> {
+        self.with(move |c| c.inbox_state(refresh)).await
+    }
+
+    /// See [`Client::inbox_states`].
+    pub async fn inbox_states(&self, inbox_ids: &[&str], refresh: bool) -> Result<Vec<InboxState>> {
+        let ids: Vec<String> = inbox_ids.iter().map(|&s| s.to_owned()).collect();
+        self.with(move |c| {
+            let refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+            c.inbox_states(&refs, refresh)
+        })
+        .await
+    }
+
+    /// See [`Client::add_account`].
+    pub async fn add_account(
+        &self,
+        existing_signer: Arc<dyn Signer>,
+        new_signer: Arc<dyn Signer>,
+    ) -> Result<()> {
+        self.with(move |c| c.add_account(existing_signer.as_ref(), new_signer.as_ref()))
+            .await
+    }
+
+    /// See [`Client::remove_account`].
+    pub async fn remove_account(
+        &self,
+        signer: Arc<dyn Signer>,
+        identifier: &AccountIdentifier,
+    ) -> Result<()> {
+        let identifier = identifier.clone();
+        self.with(move |c| c.remove_account(signer.as_ref(), &identifier))
+            .await
+    }
+
+    /// See [`Client::revoke_all_other_installations`].
+    pub async fn revoke_all_other_installations(&self, signer: Arc<dyn Signer>) -> Result<()> {
+        self.with(move |c| c.revoke_all_other_installations(signer.as_ref()))
+            .await
+    }
+
+    /// See [`Client::revoke_installations`].
+    pub async fn revoke_installations(
+        &self,
+        signer: Arc<dyn Signer>,
+        installation_ids: &[&[u8]],
+    ) -> Result<()> {
+        let ids: Vec<Vec<u8>> = installation_ids.iter().map(|id| id.to_vec()).collect();
+        self.with(move |c| {
+            let refs: Vec<&[u8]> = ids.iter().map(Vec::as_slice).collect();
+            c.revoke_installations(signer.as_ref(), &refs)
+        })
+        .await
+    }
+
+    /// See [`Client::change_recovery_identifier`].
+    pub async fn change_recovery_identifier(
+        &self,
+        signer: Arc<dyn Signer>,
+        new_identifier: &AccountIdentifier,
+    ) -> Result<()> {
+        let identifier = new_identifier.clone();
+        self.with(move |c| c.change_recovery_identifier(signer.as_ref(), &identifier))
+            .await
+    }
+}
+
+/// A cheaply cloneable async handle to a [`Conversation`].
+#[derive(Debug, Clone)]
+pub struct AsyncConversation {
+    inner: Arc<Conversation>,
+}
+
+impl From<Conversation> for AsyncConversation {
+    fn from(conversation: Conversation) -> Self {
+        Self::new(conversation)
+    }
+}
+
+impl AsyncConversation {
+    /// Wrap an existing conversation.
+    #[must_use]
+    pub fn new(conversation: Conversation) -> Self {
+        Self {
+            inner: Arc::new(conversation),
+        }
+    }
+
+    /// The underlying sync conversation.
+    #[must_use]
+    pub fn conversation(&self) -> &Conversation {
+        &self.inner
+    }
+
+    /// Run a closure against the sync conversation on the blocking pool.
+    pub async fn with<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Conversation) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        blocking(move || f(&inner)).await
+    }
+
+    /// Hex-encoded group ID.
+    #[must_use]
+    pub fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    /// See [`Conversation::sync`].
+    pub async fn sync(&self) -> Result<()> {
+        self.with(Conversation::sync).await
+    }
+
+    /// See [`Conversation::send`].
+    pub async fn send(&self, content: &[u8]) -> Result<String> {
+        let content = content.to_vec();
+        self.with(move |c| c.send(&content)).await
+    }
+
+    /// See [`Conversation::send_with`].
+    pub async fn send_with(&self, content: &[u8], opts: SendOptions) -> Result<String> {
+        let content = content.to_vec();
+        self.with(move |c| c.send_with(&content, &opts)).await
+    }
+
+    /// See [`Conversation::send_text`].
+    #[cfg(feature = "content")]
+    pub async fn send_text(&self, text: &str) -> Result<String> {
+        let text = text.to_owned();
+        self.with(move |c| c.send_text(&text)).await
+    }
+
+    /// See [`Conversation::publish_messages`].
+    pub async fn publish_messages(&self) -> Result<()> {
+        self.with(Conversation::publish_messages).await
+    }
+
+    /// See [`Conversation::messages`].
+    pub async fn messages(&self) -> Result<Vec<Message>> {
+        self.with(Conversation::messages).await
+    }
+
+    /// See [`Conversation::list_messages`].
+    pub async fn list_messages(&self, options: ListMessagesOptions) -> Result<Vec<Message>> {
+        self.with(move |c| c.list_messages(&options)).await
+    }
+
+    /// See [`Conversation::last_message`].
+    pub async fn last_message(&self) -> Result<Option<Message>> {
+        self.with(Conversation::last_message).await
+    }
+
+    /// See [`Conversation::process_envelope`].
+    pub async fn process_envelope(&self, envelope: &[u8]) -> Result<Vec<ProcessedMessage>> {
+        let envelope = envelope.to_vec();
+        self.with(move |c| c.process_envelope(&envelope)).await
+    }
+
+    /// See [`Conversation::members`].
+    pub async fn members(&self) -> Result<Vec<GroupMember>> {
+        self.with(Conversation::members).await
+    }
+
+    /// See [`Conversation::add_members_by_inbox_id`].
+    pub async fn add_members_by_inbox_id(&self, inbox_ids: &[&str]) -> Result<()> {
+        let ids: Vec<String> = inbox_ids.iter().map(|&s| s.to_owned()).collect();
+        self.with(move |c| {
+            let refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+            c.add_members_by_inbox_id(&refs)
+        })
+        .await
+    }
+
+    /// See [`Conversation::remove_members_by_inbox_id`].
+    pub async fn remove_members_by_inbox_id(&self, inbox_ids: &[&str]) -> Result<()> {
+        let ids: Vec<String> = inbox_ids.iter().map(|&s| s.to_owned()).collect();
+        self.with(move |c| {
+            let refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+            c.remove_members_by_inbox_id(&refs)
+        })
+        .await
+    }
+
+    /// See [`Conversation::leave`].
+    pub async fn leave(&self) -> Result<()> {
+        self.with(Conversation::leave).await
+    }
+
+    /// See [`Conversation::consent_state`].
+    pub async fn consent_state(&self) -> Result<ConsentState> {
+        self.with(Conversation::consent_state).await
+    }
+
+    /// See [`Conversation::set_consent`].
+    pub async fn set_consent(&self, state: ConsentState) -> Result<()> {
+        self.with(move |c| c.set_consent(state)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    fn futures_are_send(client: &AsyncClient, conv: &AsyncConversation) {
+        fn is_send<T: Send>(_: T) {}
+        is_send(client.sync_all(&[]));
+        is_send(client.dm(&Recipient::InboxId(String::new())));
+        is_send(conv.send(b""));
+        is_send(conv.list_messages(ListMessagesOptions::default()));
+    }
+
+    #[test]
+    fn handles_and_futures_are_send() {
+        assert_send_sync::<AsyncClient>();
+        assert_send_sync::<AsyncConversation>();
+        let _ = futures_are_send;
+    }
+
+    async fn double(client: AsyncClient, n: i32) -> Result<i32> {
+        client.with(move |_| Ok(n * 2)).await
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn runs_calls_on_the_blocking_pool() {
+        let client = AsyncClient::new(Client::detached());
+        let caller = std::thread::current().id();
+        let (ran_on, no_resolver) = client
+            .with(|c| Ok((std::thread::current().id(), c.resolver.is_none())))
+            .await
+            .unwrap();
+        assert_ne!(ran_on, caller);
+        assert!(no_resolver);
+
+        let tasks: Vec<_> = (0..4)
+            .map(|i| tokio::spawn(double(client.clone(), i)))
+            .collect();
+        let mut sum = 0;
+        for task in tasks {
+            sum += task.await.unwrap().unwrap();
+        }
+        assert_eq!(sum, 12);
+    }
+}
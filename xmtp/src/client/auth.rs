@@ -0,0 +1,85 @@
+#![allow(
+    unsafe_code,
+    reason = "Gateway authentication requires unsafe for FFI calls to xmtp_sys"
+)]
+//! Gateway authentication handle for decentralized (d14n) API access.
+
+use std::ptr;
+use std::sync::Arc;
+
+use crate::error::{self, Result};
+use crate::ffi::{OwnedHandle, c_str_ptr, optional_c_string, to_c_string};
+
+/// A shared credential holder for authenticating to a d14n gateway.
+///
+/// Attach it with [`ClientBuilder::auth_handle`](super::ClientBuilder::auth_handle).
+/// The client shares the underlying credential slot, so calling
+/// [`set_credential`](Self::set_credential) on this handle (or any clone of it)
+/// refreshes authentication for a running client.
+#[derive(Clone)]
+pub struct AuthHandle {
+    inner: Arc<SharedHandle>,
+}
+
+/// Owned FFI auth handle shared between clones of [`AuthHandle`].
+struct SharedHandle(OwnedHandle<xmtp_sys::XmtpFfiAuthHandle>);
+
+// SAFETY: The FFI auth handle wraps an internally synchronized credential slot;
+// all access goes through `*const` FFI calls, so shared references are thread-safe.
+unsafe impl Sync for SharedHandle {}
+
+impl AuthHandle {
+    /// Create an empty auth handle with no credential set.
+    pub fn new() -> Result<Self> {
+        let mut raw: *mut xmtp_sys::XmtpFfiAuthHandle = ptr::null_mut();
+        // SAFETY: `raw` is a valid output pointer for the new handle.
+        error::check(unsafe { xmtp_sys::xmtp_auth_handle_create(&raw mut raw) })?;
+        let handle = OwnedHandle::new(raw, xmtp_sys::xmtp_auth_handle_free)?;
+        Ok(Self {
+            inner: Arc::new(SharedHandle(handle)),
+        })
+    }
+
+    /// Set (or replace) the credential sent to the gateway.
+    ///
+    /// `header` is the HTTP header name (`None` = `authorization`), `value` the
+    /// header value, and `expires_at_seconds` the Unix expiry timestamp.
+    pub fn set_credential(
+        &self,
+        header: Option<&str>,
+        value: &str,
+        expires_at_seconds: i64,
+    ) -> Result<()> {
+        let c_name = optional_c_string(header)?;
+        let c_value = to_c_string(value)?;
+        // SAFETY: Valid handle, optional CString (or null), and CString.
+        error::check(unsafe {
+            xmtp_sys::xmtp_auth_handle_set(
+                self.as_ptr(),
+                c_str_ptr(c_name.as_ref()),
+                c_value.as_ptr(),
+                expires_at_seconds,
+            )
+        })
+    }
+
+    /// Unique ID of the underlying credential slot.
+    #[must_use]
+    pub fn id(&self) -> usize {
+        // SAFETY: `self.inner` holds a valid FFI auth handle.
+        unsafe { xmtp_sys::xmtp_auth_handle_id(self.as_ptr()) }
+    }
+
+    /// Raw const pointer for client creation.
+    pub(crate) fn as_ptr(&self) -> *const xmtp_sys::XmtpFfiAuthHandle {
+        self.inner.0.as_ptr()
+    }
+}
+
+impl std::fmt::Debug for AuthHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthHandle")
+            .field("id", &self.id())
+            .finish()
+    }
+}
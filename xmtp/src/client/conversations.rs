@@ -6,10 +6,10 @@
 
 use std::ptr;
 
-use super::Client;
+use super::{Client, ClientHandle};
 use crate::conversation::{
-    Conversation, Message, read_conversation_list_inner, read_enriched_message_list,
-    read_hmac_key_map,
+    Conversation, ConversationListItem, Message, read_conversation_item_list,
+    read_conversation_list_inner, read_enriched_message_list, read_hmac_key_map,
 };
 use crate::error::{self, Result};
 use crate::ffi::{
@@ -385,6 +385,22 @@ pub fn list_conversations(
         &self,
         options: &ListConversationsOptions,
     ) -> Result<Vec<Conversation>> {
+        read_conversation_list_inner(self.list_conversations_raw(options)?)
+    }
+
+    /// List conversations together with their last message and commit-log
+    /// fork status, in a single FFI round trip.
+    pub fn list_conversation_items(
+        &self,
+        options: &ListConversationsOptions,
+    ) -> Result<Vec<ConversationListItem>> {
+        read_conversation_item_list(self.list_conversations_raw(options)?)
+    }
+
+    fn list_conversations_raw(
+        &self,
+        options: &ListConversationsOptions,
+    ) -> Result<*mut xmtp_sys::XmtpFfiConversationList> {
         let consent_i32: Vec<i32> = options.consent_states.iter().map(|s| *s as i32).collect();
         let ffi_opts = xmtp_sys::XmtpFfiListConversationsOptions {
             conversation_type: options.conversation_type.map_or(-1, |t| t as i32),
@@ -412,7 +428,7 @@ pub fn list_conversations(
             )
         };
         error::check(rc)?;
-        read_conversation_list_inner(list)
+        Ok(list)
     }
 
     /// Sync welcomes (process new group invitations).
@@ -461,18 +477,28 @@ pub fn delete_message(&self, message_id_hex: &str) -> Result<i32> {
 
     /// Get a message by its hex-encoded ID.
     pub fn message_by_id(&self, message_id_hex: &str) -> Result<Option<Message>> {
-        let c = to_c_string(message_id_hex)?;
-        let mut out: *mut xmtp_sys::XmtpFfiEnrichedMessageList = ptr::null_mut();
-        // SAFETY: Valid handle and CString; `out` receives the message list.
+        self.handle.message_by_id(message_id_hex)
+    }
+
+    /// Decrypt a welcome envelope delivered by a push service and return the
+    /// conversations it joins.
+    ///
+    /// Intended for clients built with
+    /// [`notification_mode`](super::ClientBuilder::notification_mode).
+    pub fn process_welcome_envelope(&self, envelope: &[u8]) -> Result<Vec<Conversation>> {
+        let len = to_ffi_len(envelope.len())?;
+        let mut out: *mut xmtp_sys::XmtpFfiConversationList = ptr::null_mut();
+        // SAFETY: Valid handle and envelope buffer; `out` receives the conversation list.
         let rc = unsafe {
-            xmtp_sys::xmtp_client_get_enriched_message_by_id(
+            xmtp_sys::xmtp_client_process_streamed_welcome_message(
                 self.handle.as_ptr(),
-                c.as_ptr(),
+                envelope.as_ptr(),
+                len,
                 &raw mut out,
             )
         };
         error::check(rc)?;
-        Ok(read_enriched_message_list(out).into_iter().next())
+        read_conversation_list_inner(out)
     }
 
     /// Sync preferences (device sync groups only).
@@ -525,3 +551,21 @@ fn with_group_ffi_opts<R>(
     };
     f(&ffi)
 }
+
+impl ClientHandle {
+    /// Look up an enriched message by its hex-encoded ID.
+    pub(crate) fn message_by_id(&self, message_id_hex: &str) -> Result<Option<Message>> {
+        let c = to_c_string(message_id_hex)?;
+        let mut out: *mut xmtp_sys::XmtpFfiEnrichedMessageList = ptr::null_mut();
+        // SAFETY: Valid handle and CString; `out` receives the message list.
+        let rc = unsafe {
+            xmtp_sys::xmtp_client_get_enriched_message_by_id(
+                self.as_ptr(),
+                c.as_ptr(),
+                &raw mut out,
+            )
+        };
+        error::check(rc)?;
+        Ok(read_enriched_message_list(out).into_iter().next())
+    }
+}
@@ -0,0 +1,222 @@
+#![allow(
+    unsafe_code,
+    reason = "Device sync operations require unsafe for FFI calls to xmtp_sys"
+)]
+//! Device sync: sync requests, archive exchange between installations, and
+//! local archive export, import, and inspection.
+
+use std::ffi::CStr;
+use std::ptr;
+
+use super::Client;
+use crate::error::{self, Result};
+use crate::ffi::{FfiList, c_str_ptr, ffi_usize, optional_c_string, to_c_string, to_ffi_len};
+use crate::types::{ArchiveElement, ArchiveMetadata, ArchiveOptions, AvailableArchive, SyncResult};
+
+/// Minimum archive encryption key length in bytes.
+const ARCHIVE_KEY_LEN: usize = 32;
+
+impl Client {
+    /// Send a device sync request to retrieve records from another installation.
+    pub fn request_device_sync(&self) -> Result<()> {
+        let opts = xmtp_sys::XmtpFfiArchiveOptions::default();
+        // SAFETY: Valid handle; `opts` is a stack-allocated default struct.
+        error::check(unsafe {
+            xmtp_sys::xmtp_device_sync_send_request(
+                self.handle.as_ptr(),
+                &raw const opts,
+                ptr::null(),
+            )
+        })
+    }
+
+    /// Upload an archive to `server_url` and announce it to the sync group under `pin`.
+    ///
+    /// Other installations can find it via
+    /// [`available_archives`](Self::available_archives) and import it with
+    /// [`process_archive`](Self::process_archive).
+    pub fn send_archive(&self, opts: &ArchiveOptions, server_url: &str, pin: &str) -> Result<()> {
+        let c_url = to_c_string(server_url)?;
+        let c_pin = to_c_string(pin)?;
+        let ffi_opts = archive_opts_to_ffi(opts);
+        // SAFETY: Valid handle, FFI options, and CStrings.
+        error::check(unsafe {
+            xmtp_sys::xmtp_device_sync_send_archive(
+                self.handle.as_ptr(),
+                &raw const ffi_opts,
+                c_url.as_ptr(),
+                c_pin.as_ptr(),
+            )
+        })
+    }
+
+    /// List archives offered by other installations within the last `days_cutoff` days.
+    /// Entries without a valid UTF-8 PIN are skipped, since they cannot be
+    /// requested by PIN.
+    pub fn available_archives(&self, days_cutoff: i64) -> Result<Vec<AvailableArchive>> {
+        let mut out: *mut xmtp_sys::XmtpFfiAvailableArchiveList = ptr::null_mut();
+        // SAFETY: Valid handle; `out` receives the archive list.
+        let rc = unsafe {
+            xmtp_sys::xmtp_device_sync_list_available_archives(
+                self.handle.as_ptr(),
+                days_cutoff,
+                &raw mut out,
+            )
+        };
+        error::check(rc)?;
+        Ok(read_available_archive_list(out))
+    }
+
+    /// Download and import the archive announced under `pin`.
+    ///
+    /// Pass `None` to process the most recent archive.
+    pub fn process_archive(&self, pin: Option<&str>) -> Result<()> {
+        let c_pin = optional_c_string(pin)?;
+        // SAFETY: Valid handle and optional CString (or null).
+        error::check(unsafe {
+            xmtp_sys::xmtp_device_sync_process_archive(
+                self.handle.as_ptr(),
+                c_str_ptr(c_pin.as_ref()),
+            )
+        })
+    }
+
+    /// Sync welcomes and all device sync groups.
+    pub fn sync_device_sync_groups(&self) -> Result<SyncResult> {
+        let (mut synced, mut eligible) = (0i32, 0i32);
+        // SAFETY: Valid handle; output pointers receive sync counts.
+        let rc = unsafe {
+            xmtp_sys::xmtp_device_sync_sync_all(
+                self.handle.as_ptr(),
+                &raw mut synced,
+                &raw mut eligible,
+            )
+        };
+        error::check(rc)?;
+        Ok(SyncResult {
+            synced: synced as u32,
+            eligible: eligible as u32,
+        })
+    }
+
+    /// Export an encrypted archive of this installation to a local file.
+    ///
+    /// `key` must be at least 32 bytes; only the first 32 are used.
+    pub fn create_archive(&self, path: &str, key: &[u8], opts: &ArchiveOptions) -> Result<()> {
+        check_key(key)?;
+        let c_path = to_c_string(path)?;
+        let ffi_opts = archive_opts_to_ffi(opts);
+        // SAFETY: Valid handle, CString, FFI options, and key slice with matching length.
+        error::check(unsafe {
+            xmtp_sys::xmtp_device_sync_create_archive(
+                self.handle.as_ptr(),
+                c_path.as_ptr(),
+                &raw const ffi_opts,
+                key.as_ptr(),
+                to_ffi_len(key.len())?,
+            )
+        })
+    }
+
+    /// Import a previously exported archive file into this installation.
+    ///
+    /// `key` must be the key the archive was created with.
+    pub fn import_archive(&self, path: &str, key: &[u8]) -> Result<()> {
+        check_key(key)?;
+        let c_path = to_c_string(path)?;
+        // SAFETY: Valid handle, CString, and key slice with matching length.
+        error::check(unsafe {
+            xmtp_sys::xmtp_device_sync_import_archive(
+                self.handle.as_ptr(),
+                c_path.as_ptr(),
+                key.as_ptr(),
+                to_ffi_len(key.len())?,
+            )
+        })
+    }
+}
+
+/// Read the metadata of an archive file without importing it. No client required.
+pub fn archive_metadata(path: &str, key: &[u8]) -> Result<ArchiveMetadata> {
+    check_key(key)?;
+    let c_path = to_c_string(path)?;
+    let mut version = 0u16;
+    let mut exported_at_ns = 0i64;
+    let mut elements = 0i32;
+    let mut start_ns = 0i64;
+    let mut end_ns = 0i64;
+    // SAFETY: Valid CString and key slice; all output pointers are valid stack locations.
+    let rc = unsafe {
+        xmtp_sys::xmtp_device_sync_archive_metadata(
+            c_path.as_ptr(),
+            key.as_ptr(),
+            to_ffi_len(key.len())?,
+            &raw mut version,
+            &raw mut exported_at_ns,
+            &raw mut elements,
+            &raw mut start_ns,
+            &raw mut end_ns,
+        )
+    };
+    error::check(rc)?;
+    Ok(ArchiveMetadata {
+        version,
+        exported_at_ns,
+        elements: ArchiveElement::from_bits(elements),
+        start_ns: (start_ns > 0).then_some(start_ns),
+        end_ns: (end_ns > 0).then_some(end_ns),
+    })
+}
+
+/// Convert `ArchiveOptions` to the FFI struct.
+pub(crate) fn archive_opts_to_ffi(opts: &ArchiveOptions) -> xmtp_sys::XmtpFfiArchiveOptions {
+    xmtp_sys::XmtpFfiArchiveOptions {
+        elements: ArchiveElement::to_bits(&opts.elements),
+        start_ns: opts.start_ns,
+        end_ns: opts.end_ns,
+        exclude_disappearing_messages: i32::from(opts.exclude_disappearing),
+    }
+}
+
+/// Read an FFI available archive list.
+fn read_available_archive_list(
+    ptr: *mut xmtp_sys::XmtpFfiAvailableArchiveList,
+) -> Vec<AvailableArchive> {
+    let list = FfiList::new(
+        ptr,
+        xmtp_sys::xmtp_available_archive_list_len,
+        xmtp_sys::xmtp_available_archive_list_free,
+    );
+    let mut archives = Vec::with_capacity(ffi_usize(list.len()));
+    for i in 0..list.len() {
+        // SAFETY: `list` is a valid FFI list and `i` is within bounds.
+        let pin_ptr = unsafe { xmtp_sys::xmtp_available_archive_pin(list.as_ptr(), i) };
+        if pin_ptr.is_null() {
+            continue;
+        }
+        // SAFETY: `pin_ptr` is a non-null borrowed C string owned by the list.
+        let pin = unsafe { CStr::from_ptr(pin_ptr) };
+        // A lossily decoded pin would name no archive, so skip the entry.
+        let Ok(pin) = pin.to_str() else {
+            continue;
+        };
+        // SAFETY: `list` is a valid FFI list and `i` is within bounds.
+        let exported_at_ns =
+            unsafe { xmtp_sys::xmtp_available_archive_exported_at_ns(list.as_ptr(), i) };
+        archives.push(AvailableArchive {
+            pin: pin.to_owned(),
+            exported_at_ns,
+        });
+    }
+    archives
+}
+
+/// Reject archive keys the FFI layer would refuse.
+fn check_key(key: &[u8]) -> Result<()> {
+    if key.len() < ARCHIVE_KEY_LEN {
+        return Err(crate::XmtpError::InvalidArgument(format!(
+            "archive key must be at least {ARCHIVE_KEY_LEN} bytes"
+        )));
+    }
+    Ok(())
+}
@@ -4,15 +4,23 @@
 )]
 //! XMTP client — the primary entry point for the SDK.
 
+mod auth;
 mod conversations;
+mod device_sync;
 mod identity;
 
+use std::collections::BTreeMap;
 use std::ffi::c_char;
 use std::ptr;
+use std::sync::Arc;
+
+pub use auth::AuthHandle;
+pub use device_sync::archive_metadata;
 
 use crate::error::{self, Result};
 use crate::ffi::{
-    FfiList, OwnedHandle, ffi_usize, read_borrowed_strings, take_c_string, to_c_string, to_ffi_len,
+    FfiList, OwnedHandle, borrow_c_string, ffi_usize, read_borrowed_strings, take_c_string,
+    to_c_string, to_c_string_array, to_ffi_len,
 };
 use crate::types::{
     AccountIdentifier, ApiStats, ConsentEntityType, ConsentState, Env, IdentifierKind,
@@ -78,16 +86,61 @@ pub fn libxmtp_version() -> Result<String> {
 
 /// A connected XMTP client.
 pub struct Client {
-    pub(crate) handle: OwnedHandle<xmtp_sys::XmtpFfiClient>,
+    pub(crate) handle: Arc<ClientHandle>,
     pub(crate) resolver: Option<Box<dyn crate::resolve::Resolver>>,
+    #[cfg(feature = "content")]
+    pub(crate) codecs: Arc<crate::content::CodecRegistry>,
+}
+
+/// Owned FFI client handle, shared with streams that look up messages from
+/// their callback and therefore must keep the client alive.
+#[derive(Debug)]
+pub(crate) struct ClientHandle(OwnedHandle<xmtp_sys::XmtpFfiClient>);
+
+// SAFETY: Shared references only ever reach libxmtp through `as_ptr`. Every
+// `xmtp_*` entry point that takes a client takes `*const XmtpFfiClient` and
+// only borrows it as `&FfiClient`; the sole `*mut` entry point,
+// `xmtp_client_free`, runs from `OwnedHandle::drop`, which has exclusive
+// access. `FfiClient` holds an `Arc<xmtp_mls::Client>`, which libxmtp itself
+// clones into tasks on its multi-threaded runtime, and two immutable strings.
+// FFI errors are reported through a thread-local, so concurrent calls do not
+// see each other's errors.
+unsafe impl Sync for ClientHandle {}
+
+impl ClientHandle {
+    /// Const pointer for FFI calls.
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *const xmtp_sys::XmtpFfiClient {
+        self.0.as_ptr()
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+impl Client {
+    /// A client whose handle must never reach the FFI, for testing the
+    /// pure-Rust layers without linking libxmtp.
+    pub(crate) fn detached() -> Self {
+        const unsafe extern "C" fn keep(_: *mut xmtp_sys::XmtpFfiClient) {}
+        let dangling = ptr::NonNull::dangling().as_ptr();
+        Self {
+            handle: Arc::new(ClientHandle(
+                OwnedHandle::new(dangling, keep).unwrap_or_else(|_| unreachable!()),
+            )),
+            resolver: None,
+            #[cfg(feature = "content")]
+            codecs: Arc::default(),
+        }
+    }
 }
 
 impl std::fmt::Debug for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Client")
-            .field("handle", &self.handle)
-            .field("resolver", &self.resolver.is_some())
-            .finish()
+        let mut d = f.debug_struct("Client");
+        d.field("handle", &self.handle)
+            .field("resolver", &self.resolver.is_some());
+        #[cfg(feature = "content")]
+        d.field("codecs", &self.codecs);
+        d.finish()
     }
 }
 
@@ -248,6 +301,44 @@ pub fn inbox_states(&self, inbox_ids: &[&str], refresh: bool) -> Result<Vec<Inbo
         read_inbox_state_list(out)
     }
 
+    /// Number of identity updates for each of `inbox_ids`, keyed by inbox ID.
+    ///
+    /// Useful for watching inboxes that approach the identity-update limit.
+    pub fn inbox_update_counts(
+        &self,
+        inbox_ids: &[&str],
+        refresh: bool,
+    ) -> Result<BTreeMap<String, u32>> {
+        let (_owned, ptrs) = to_c_string_array(inbox_ids)?;
+        let mut out: *mut xmtp_sys::XmtpFfiInboxUpdateCountList = ptr::null_mut();
+        // SAFETY: All CString pointers and handle are valid; `out` receives the result.
+        let rc = unsafe {
+            xmtp_sys::xmtp_client_fetch_inbox_updates_count(
+                self.handle.as_ptr(),
+                ptrs.as_ptr(),
+                to_ffi_len(ptrs.len())?,
+                i32::from(refresh),
+                &raw mut out,
+            )
+        };
+        error::check(rc)?;
+        Ok(read_inbox_update_count_list(out))
+    }
+
+    /// Number of identity updates for this client's own inbox.
+    pub fn own_inbox_update_count(&self, refresh: bool) -> Result<u32> {
+        let mut count = 0u32;
+        // SAFETY: Valid handle; `count` receives the update count.
+        error::check(unsafe {
+            xmtp_sys::xmtp_client_fetch_own_inbox_updates_count(
+                self.handle.as_ptr(),
+                i32::from(refresh),
+                &raw mut count,
+            )
+        })?;
+        Ok(count)
+    }
+
     /// Sign text with the client's installation key. Returns signature bytes.
     pub fn sign_with_installation_key(&self, text: &str) -> Result<Vec<u8>> {
         let c = to_c_string(text)?;
@@ -388,7 +479,7 @@ pub fn clear_stats(&self) -> Result<()> {
 
     /// Fetch key package statuses for a list of installation IDs (hex).
     pub fn key_package_statuses(&self, installation_ids: &[&str]) -> Result<Vec<KeyPackageStatus>> {
-        let (_owned, ptrs) = crate::ffi::to_c_string_array(installation_ids)?;
+        let (_owned, ptrs) = to_c_string_array(installation_ids)?;
         let len = to_ffi_len(ptrs.len())?;
         let mut out: *mut xmtp_sys::XmtpFfiKeyPackageStatusList = ptr::null_mut();
         // SAFETY: Valid handle and CString array with matching length; `out` receives the result.
@@ -403,19 +494,6 @@ pub fn key_package_statuses(&self, installation_ids: &[&str]) -> Result<Vec<KeyP
         error::check(rc)?;
         Ok(read_key_package_status_list(out))
     }
-
-    /// Send a device sync request to retrieve records from another installation.
-    pub fn request_device_sync(&self) -> Result<()> {
-        let opts = xmtp_sys::XmtpFfiArchiveOptions::default();
-        // SAFETY: Valid handle; `opts` is a stack-allocated default struct.
-        error::check(unsafe {
-            xmtp_sys::xmtp_device_sync_send_request(
-                self.handle.as_ptr(),
-                &raw const opts,
-                ptr::null(),
-            )
-        })
-    }
 }
 
 /// Builder for constructing a [`Client`].
@@ -427,11 +505,14 @@ pub struct ClientBuilder {
     app_version: Option<String>,
     api_url: Option<String>,
     gateway_host: Option<String>,
+    auth_handle: Option<AuthHandle>,
     nonce: u64,
     disable_device_sync: bool,
     allow_offline: bool,
     notification_mode: bool,
     resolver: Option<Box<dyn crate::resolve::Resolver>>,
+    #[cfg(feature = "content")]
+    codecs: crate::content::CodecRegistry,
 }
 
 impl std::fmt::Debug for ClientBuilder {
@@ -480,6 +561,15 @@ pub fn gateway_host(mut self, h: impl Into<String>) -> Self {
         self
     }
 
+    /// Attach a gateway [`AuthHandle`] used to authenticate to `gateway_host`.
+    ///
+    /// Keep a clone of the handle to refresh the credential while the client runs.
+    #[must_use]
+    pub fn auth_handle(mut self, h: &AuthHandle) -> Self {
+        self.auth_handle = Some(h.clone());
+        self
+    }
+
     /// Set a custom app version string.
     #[must_use]
     pub fn app_version(mut self, v: impl Into<String>) -> Self {
@@ -527,6 +617,15 @@ pub fn resolver(mut self, r: impl crate::resolve::Resolver + 'static) -> Self {
         self
     }
 
+    /// Register a [`ContentCodec`](crate::content::ContentCodec) for an
+    /// app-specific content type. See [`Client::decode`].
+    #[cfg(feature = "content")]
+    #[must_use]
+    pub fn codec<C: crate::content::ContentCodec>(mut self, codec: C) -> Self {
+        self.codecs.register(codec);
+        self
+    }
+
     /// Build the client, registering identity with `signer` if needed.
     pub fn build(self, signer: &dyn Signer) -> Result<Client> {
         let ident = signer.identifier();
@@ -571,7 +670,10 @@ fn build_inner(self, address: &str, kind: IdentifierKind) -> Result<Client> {
             account_identifier: c_account.as_ptr(),
             identifier_kind: kind as i32,
             nonce,
-            auth_handle: ptr::null(),
+            auth_handle: self
+                .auth_handle
+                .as_ref()
+                .map_or(ptr::null(), AuthHandle::as_ptr),
             app_version: c_app.as_ref().map_or(ptr::null(), |c| c.as_ptr()),
             device_sync_worker_mode: i32::from(self.disable_device_sync),
             allow_offline: i32::from(self.allow_offline),
@@ -585,8 +687,10 @@ fn build_inner(self, address: &str, kind: IdentifierKind) -> Result<Client> {
         error::check(unsafe { xmtp_sys::xmtp_client_create(&raw const opts, &raw mut raw) })?;
         let handle = OwnedHandle::new(raw, xmtp_sys::xmtp_client_free)?;
         Ok(Client {
-            handle,
+            handle: Arc::new(ClientHandle(handle)),
             resolver: self.resolver,
+            #[cfg(feature = "content")]
+            codecs: Arc::new(self.codecs),
         })
     }
 }
@@ -600,6 +704,23 @@ pub(crate) fn sign_request(
     let ptr = unsafe { xmtp_sys::xmtp_signature_request_text(sig_req.as_ptr()) };
     // SAFETY: `ptr` is a C string allocated by the FFI layer.
     let text = unsafe { take_c_string(ptr) }?;
+    if signer.identifier().kind == IdentifierKind::Passkey {
+        let sig = signer.sign_passkey(&text)?;
+        // SAFETY: Valid signature request handle and byte slices with matching lengths.
+        return error::check(unsafe {
+            xmtp_sys::xmtp_signature_request_add_passkey(
+                sig_req.as_ptr(),
+                sig.public_key.as_ptr(),
+                to_ffi_len(sig.public_key.len())?,
+                sig.signature.as_ptr(),
+                to_ffi_len(sig.signature.len())?,
+                sig.authenticator_data.as_ptr(),
+                to_ffi_len(sig.authenticator_data.len())?,
+                sig.client_data_json.as_ptr(),
+                to_ffi_len(sig.client_data_json.len())?,
+            )
+        });
+    }
     let signature = signer.sign(&text)?;
     if signer.is_smart_wallet() {
         let ident = signer.identifier();
@@ -764,6 +885,31 @@ fn read_key_package_status_list(
     statuses
 }
 
+/// Read an FFI inbox update count list into a map keyed by inbox ID.
+fn read_inbox_update_count_list(
+    ptr: *mut xmtp_sys::XmtpFfiInboxUpdateCountList,
+) -> BTreeMap<String, u32> {
+    let list = FfiList::new(
+        ptr,
+        xmtp_sys::xmtp_inbox_update_count_list_len,
+        xmtp_sys::xmtp_inbox_update_count_list_free,
+    );
+    let mut counts = BTreeMap::new();
+    for i in 0..list.len() {
+        // SAFETY: `list` is a valid FFI list and `i` is within bounds.
+        let p = unsafe { xmtp_sys::xmtp_inbox_update_count_list_get(list.as_ptr(), i) };
+        if p.is_null() {
+            continue;
+        }
+        // SAFETY: `p` is non-null and points to a valid FFI struct owned by the list.
+        let item = unsafe { &*p };
+        // SAFETY: `inbox_id` is a C string owned (and freed) by the list.
+        let inbox_id = unsafe { borrow_c_string(item.inbox_id) };
+        counts.insert(inbox_id, item.count);
+    }
+    counts
+}
+
 /// Read an FFI inbox state list into `Vec<InboxState>`.
 fn read_inbox_state_list(ptr: *mut xmtp_sys::XmtpFfiInboxStateList) -> Result<Vec<InboxState>> {
     let list = FfiList::new(
@@ -0,0 +1,182 @@
+//! Interactive buttons (`coinbase.com/actions:1.0`) and the responses to them
+//! (`coinbase.com/intent:1.0`).
+
+use std::collections::BTreeMap;
+
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+
+use super::{ACTIONS, EncodedContent, INTENT};
+use crate::conversation::Conversation;
+use crate::error::{Result, XmtpError};
+
+/// A set of buttons for the recipient to choose from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Actions {
+    /// Identifier echoed back in [`Intent::id`].
+    pub id: String,
+    /// Prompt shown above the buttons.
+    pub description: String,
+    /// The buttons, in display order.
+    pub actions: Vec<Action>,
+    /// When the whole set stops accepting responses (ISO-8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+/// One button in an [`Actions`] message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    /// Identifier echoed back in [`Intent::action_id`].
+    pub id: String,
+    /// Button label.
+    pub label: String,
+    /// Optional icon URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    /// Visual style.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<ActionStyle>,
+    /// When this button stops accepting responses (ISO-8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+/// Visual style of an [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionStyle {
+    /// Emphasized default choice.
+    Primary,
+    /// Neutral choice.
+    Secondary,
+    /// Destructive choice.
+    Danger,
+}
+
+/// The recipient's choice in response to an [`Actions`] message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Intent {
+    /// [`Actions::id`] being answered.
+    pub id: String,
+    /// [`Action::id`] that was chosen.
+    pub action_id: String,
+    /// Optional app-defined context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+/// Encode a set of actions into protobuf bytes.
+///
+/// The fallback lists the buttons as numbered options.
+///
+/// # Errors
+///
+/// Returns an error if the actions cannot be serialized.
+pub fn encode_actions(actions: &Actions) -> Result<Vec<u8>> {
+    let content = serde_json::to_vec(actions)
+        .map_err(|e| XmtpError::InvalidArgument(format!("actions: {e}")))?;
+    let options: Vec<String> = actions
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(i, a)| format!("[{}] {}", i + 1, a.label))
+        .collect();
+    let fallback = format!(
+        "{}\n\n{}\n\nReply with the number to select",
+        actions.description,
+        options.join("\n")
+    );
+    Ok(EncodedContent {
+        r#type: Some(ACTIONS.id()),
+        parameters: BTreeMap::new(),
+        fallback: Some(fallback),
+        content,
+        compression: None,
+    }
+    .encode_to_vec())
+}
+
+/// Encode an intent into protobuf bytes.
+///
+/// # Errors
+///
+/// Returns an error if the intent cannot be serialized.
+pub fn encode_intent(intent: &Intent) -> Result<Vec<u8>> {
+    let content = serde_json::to_vec(intent)
+        .map_err(|e| XmtpError::InvalidArgument(format!("intent: {e}")))?;
+    Ok(EncodedContent {
+        r#type: Some(INTENT.id()),
+        parameters: BTreeMap::new(),
+        fallback: Some(format!("User selected action: {}", intent.action_id)),
+        content,
+        compression: None,
+    }
+    .encode_to_vec())
+}
+
+/// Parse the JSON payload of an actions message.
+pub(super) fn decode_actions(content: &[u8]) -> Result<Actions> {
+    serde_json::from_slice(content).map_err(|e| XmtpError::Ffi(format!("actions decode: {e}")))
+}
+
+/// Parse the JSON payload of an intent message.
+pub(super) fn decode_intent(content: &[u8]) -> Result<Intent> {
+    serde_json::from_slice(content).map_err(|e| XmtpError::Ffi(format!("intent decode: {e}")))
+}
+
+impl Conversation {
+    /// Present a set of buttons.
+    pub fn send_actions(&self, actions: &Actions) -> Result<String> {
+        self.send(&encode_actions(actions)?)
+    }
+
+    /// Answer an [`Actions`] message with the chosen button.
+    pub fn send_intent(&self, intent: &Intent) -> Result<String> {
+        self.send(&encode_intent(intent)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{Content, decode};
+
+    #[test]
+    fn actions_and_intent_round_trip() {
+        let actions = Actions {
+            id: "menu".into(),
+            description: "Pick one".into(),
+            actions: vec![
+                Action {
+                    id: "yes".into(),
+                    label: "Yes".into(),
+                    style: Some(ActionStyle::Primary),
+                    ..Default::default()
+                },
+                Action {
+                    id: "no".into(),
+                    label: "No".into(),
+                    style: Some(ActionStyle::Danger),
+                    ..Default::default()
+                },
+            ],
+            expires_at: None,
+        };
+        let raw = encode_actions(&actions).unwrap();
+        let ec = EncodedContent::decode(raw.as_slice()).unwrap();
+        assert!(ec.fallback.unwrap().contains("[2] No"));
+        assert!(matches!(decode(&raw).unwrap(), Content::Actions(a) if a == actions));
+
+        let intent = Intent {
+            id: "menu".into(),
+            action_id: "yes".into(),
+            metadata: Some(BTreeMap::from([("n".into(), serde_json::json!(1))])),
+        };
+        let raw_intent = encode_intent(&intent).unwrap();
+        assert!(matches!(decode(&raw_intent).unwrap(), Content::Intent(i) if i == intent));
+    }
+}
@@ -0,0 +1,375 @@
+//! Pluggable codecs for application-defined content types.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use prost::Message as ProstMessage;
+
+use super::{
+    Content, ContentTypeId, DEFAULT_MAX_DECOMPRESSED_LEN, EncodedContent, decode_builtin, parse,
+};
+use crate::client::Client;
+use crate::conversation::{Conversation, Message, MessagePreview, ProcessedMessage};
+use crate::error::Result;
+use crate::stream::DecodedMessage;
+use crate::types::{ListMessagesOptions, SendOptions};
+
+/// Encoder/decoder for one content type.
+///
+/// Implement this for an app-specific payload, register it with
+/// [`ClientBuilder::codec`](crate::ClientBuilder::codec), and decoded messages
+/// of that type come back as [`Content::Custom`]:
+///
+/// ```
+/// use xmtp::content::{ContentCodec, ContentTypeId, EncodedContent};
+///
+/// struct Ping;
+///
+/// impl ContentCodec for Ping {
+///     type Value = u64;
+///
+///     fn content_type(&self) -> ContentTypeId {
+///         ContentTypeId {
+///             authority_id: "example.com".into(),
+///             type_id: "ping".into(),
+///             version_major: 1,
+///             version_minor: 0,
+///         }
+///     }
+///
+///     fn encode(&self, value: &u64) -> xmtp::Result<EncodedContent> {
+///         Ok(EncodedContent {
+///             content: value.to_be_bytes().to_vec(),
+///             ..Default::default()
+///         })
+///     }
+///
+///     fn decode(&self, content: &EncodedContent) -> xmtp::Result<u64> {
+///         let bytes = content.content.as_slice().try_into().map_err(|_| {
+///             xmtp::XmtpError::InvalidArgument("ping must be 8 bytes".into())
+///         })?;
+///         Ok(u64::from_be_bytes(bytes))
+///     }
+/// }
+/// ```
+pub trait ContentCodec: Send + Sync + 'static {
+    /// The decoded value type.
+    type Value: Send + Sync + 'static;
+
+    /// Content type this codec handles. Decoding matches on authority and
+    /// type name; messages with a different major version are not passed to
+    /// the codec.
+    fn content_type(&self) -> ContentTypeId;
+
+    /// Encode `value` into an envelope. The content type and fallback are
+    /// filled in from this codec if left empty.
+    fn encode(&self, value: &Self::Value) -> Result<EncodedContent>;
+
+    /// Decode an envelope of this codec's content type.
+    fn decode(&self, content: &EncodedContent) -> Result<Self::Value>;
+
+    /// Text shown by clients that do not understand this content type.
+    fn fallback(&self, _value: &Self::Value) -> Option<String> {
+        None
+    }
+
+    /// Whether messages of this value should trigger push notifications.
+    fn should_push(&self, _value: &Self::Value) -> bool {
+        true
+    }
+}
+
+/// Object-safe view of a [`ContentCodec`] with its value type erased.
+trait ErasedCodec: Send + Sync {
+    fn erased_type(&self) -> ContentTypeId;
+    fn decode_any(&self, content: &EncodedContent) -> Result<Arc<dyn Any + Send + Sync>>;
+}
+
+impl<C: ContentCodec> ErasedCodec for C {
+    fn erased_type(&self) -> ContentTypeId {
+        self.content_type()
+    }
+
+    fn decode_any(&self, content: &EncodedContent) -> Result<Arc<dyn Any + Send + Sync>> {
+        Ok(Arc::new(self.decode(content)?))
+    }
+}
+
+/// A decoded value produced by a registered [`ContentCodec`].
+#[derive(Clone)]
+pub struct CustomContent {
+    /// Content type of the encoded message.
+    pub content_type: ContentTypeId,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomContent {
+    /// The decoded value, if it is a `T` (the codec's
+    /// [`Value`](ContentCodec::Value)).
+    #[must_use]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
+
+impl fmt::Debug for CustomContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomContent")
+            .field("content_type", &self.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Content codecs keyed by `(authority, type name)`.
+///
+/// Registered codecs take precedence over the built-in content types, so a
+/// registry can also override how standard types decode.
+#[derive(Clone)]
+pub struct CodecRegistry {
+    codecs: BTreeMap<(String, String), Arc<dyn ErasedCodec>>,
+    max_decompressed_len: usize,
+}
+
+impl Default for CodecRegistry {
+    fn default() -> Self {
+        Self {
+            codecs: BTreeMap::new(),
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+        }
+    }
+}
+
+impl CodecRegistry {
+    /// An empty registry (built-in content types only).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `codec`, replacing any codec for the same content type.
+    pub fn register<C: ContentCodec>(&mut self, codec: C) {
+        let t = codec.content_type();
+        self.codecs
+            .insert((t.authority_id, t.type_id), Arc::new(codec));
+    }
+
+    /// Limit on the inflated size of compressed payloads (default
+    /// [`DEFAULT_MAX_DECOMPRESSED_LEN`]).
+    pub const fn set_max_decompressed_len(&mut self, max_len: usize) {
+        self.max_decompressed_len = max_len;
+    }
+
+    /// Whether a codec is registered for `content_type`.
+    #[must_use]
+    pub fn contains(&self, content_type: &ContentTypeId) -> bool {
+        self.codecs.contains_key(&key(content_type))
+    }
+
+    /// Decode raw `Message::content` bytes, trying registered codecs before
+    /// the built-in content types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed, inflates beyond the
+    /// [limit](Self::set_max_decompressed_len), or the codec rejects it.
+    pub fn decode(&self, raw: &[u8]) -> Result<Content> {
+        let ec = parse(raw, self.max_decompressed_len)?;
+        let codec = ec.r#type.as_ref().and_then(|t| {
+            self.codecs
+                .get(&key(t))
+                .filter(|c| c.erased_type().is_compatible(t))
+        });
+        match codec {
+            Some(codec) => Ok(Content::Custom(CustomContent {
+                value: codec.decode_any(&ec)?,
+                content_type: ec.r#type.unwrap_or_default(),
+            })),
+            None => decode_builtin(ec, raw),
+        }
+    }
+}
+
+impl fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CodecRegistry")
+            .field(
+                "codecs",
+                &self
+                    .codecs
+                    .keys()
+                    .map(|(a, t)| format!("{a}/{t}"))
+                    .collect::<Vec<_>>(),
+            )
+            .field("max_decompressed_len", &self.max_decompressed_len)
+            .finish()
+    }
+}
+
+fn key(t: &ContentTypeId) -> (String, String) {
+    (t.authority_id.clone(), t.type_id.clone())
+}
+
+/// Encode `value` with `codec` into protobuf bytes ready for
+/// [`Conversation::send`].
+///
+/// # Errors
+///
+/// Returns any error from [`ContentCodec::encode`].
+pub fn encode_with<C: ContentCodec>(codec: &C, value: &C::Value) -> Result<Vec<u8>> {
+    let mut ec = codec.encode(value)?;
+    if ec.r#type.is_none() {
+        ec.r#type = Some(codec.content_type());
+    }
+    if ec.fallback.is_none() {
+        ec.fallback = codec.fallback(value);
+    }
+    Ok(ec.encode_to_vec())
+}
+
+impl Message {
+    /// Decode the content with `registry`, so registered custom types come
+    /// back as [`Content::Custom`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed or the codec rejects it.
+    pub fn decode_with(&self, registry: &CodecRegistry) -> Result<Content> {
+        registry.decode(&self.content)
+    }
+}
+
+impl Client {
+    /// Codecs registered with [`ClientBuilder::codec`](crate::ClientBuilder::codec).
+    #[must_use]
+    pub fn codecs(&self) -> &CodecRegistry {
+        &self.codecs
+    }
+
+    /// Decode a message with this client's [`codecs`](Self::codecs).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed or the codec rejects it.
+    pub fn decode(&self, message: &Message) -> Result<Content> {
+        message.decode_with(&self.codecs)
+    }
+
+    /// List a conversation's messages and decode each with this client's
+    /// [`codecs`](Self::codecs), like
+    /// [`stream::decoded_messages`](crate::stream::decoded_messages) does for
+    /// new ones.
+    pub fn decoded_messages(
+        &self,
+        conversation: &Conversation,
+        options: &ListMessagesOptions,
+    ) -> Result<Vec<DecodedMessage>> {
+        Ok(conversation
+            .list_messages(options)?
+            .into_iter()
+            .map(|m| DecodedMessage::new(m, &self.codecs))
+            .collect())
+    }
+}
+
+impl MessagePreview {
+    /// Decode the content with `registry`. See [`Message::decode_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed or the codec rejects it.
+    pub fn decode_with(&self, registry: &CodecRegistry) -> Result<Content> {
+        registry.decode(&self.content)
+    }
+}
+
+impl ProcessedMessage {
+    /// Decode the content with `registry`. See [`Message::decode_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed or the codec rejects it.
+    pub fn decode_with(&self, registry: &CodecRegistry) -> Result<Content> {
+        registry.decode(&self.content)
+    }
+}
+
+impl Conversation {
+    /// Send a value encoded by `codec`, honoring its
+    /// [`should_push`](ContentCodec::should_push).
+    pub fn send_content<C: ContentCodec>(&self, codec: &C, value: &C::Value) -> Result<String> {
+        let opts = SendOptions {
+            should_push: codec.should_push(value),
+        };
+        self.send_with(&encode_with(codec, value)?, &opts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{decode, encode_text};
+    use crate::error::XmtpError;
+
+    struct Ping;
+
+    impl ContentCodec for Ping {
+        type Value = u64;
+
+        fn content_type(&self) -> ContentTypeId {
+            ContentTypeId {
+                authority_id: "example.com".into(),
+                type_id: "ping".into(),
+                version_major: 1,
+                version_minor: 0,
+            }
+        }
+
+        fn encode(&self, value: &u64) -> Result<EncodedContent> {
+            Ok(EncodedContent {
+                content: value.to_be_bytes().to_vec(),
+                ..Default::default()
+            })
+        }
+
+        fn decode(&self, content: &EncodedContent) -> Result<u64> {
+            let bytes = content
+                .content
+                .as_slice()
+                .try_into()
+                .map_err(|_| XmtpError::InvalidArgument("ping must be 8 bytes".into()))?;
+            Ok(u64::from_be_bytes(bytes))
+        }
+
+        fn fallback(&self, value: &u64) -> Option<String> {
+            Some(format!("ping {value}"))
+        }
+    }
+
+    #[test]
+    fn registered_codec_round_trips_and_builtins_still_decode() {
+        let mut registry = CodecRegistry::new();
+        registry.register(Ping);
+        assert!(registry.contains(&Ping.content_type()));
+
+        let raw = encode_with(&Ping, &42).unwrap();
+        let ec = EncodedContent::decode(raw.as_slice()).unwrap();
+        assert_eq!(ec.fallback.as_deref(), Some("ping 42"));
+
+        let decoded = registry.decode(&raw).unwrap();
+        assert!(decoded.is_custom());
+        assert_eq!(decoded.as_custom::<u64>(), Some(&42));
+        assert!(decoded.as_custom::<String>().is_none());
+
+        assert!(decode(&raw).unwrap().is_unknown());
+        let v2 = EncodedContent {
+            r#type: Some(ContentTypeId::new("example.com", "ping", 2, 0)),
+            content: 7u64.to_be_bytes().to_vec(),
+            ..Default::default()
+        };
+        assert!(registry.decode(&v2.encode_to_vec()).unwrap().is_unknown());
+        let text = registry.decode(&encode_text("hi")).unwrap();
+        assert_eq!(text.as_text(), Some("hi"));
+    }
+}
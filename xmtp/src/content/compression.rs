@@ -0,0 +1,174 @@
+//! Transparent deflate/gzip compression of [`EncodedContent`] payloads.
+
+use std::io::{Read, Write};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use prost::Message as ProstMessage;
+
+use super::{Compression, EncodedContent};
+use crate::error::{Result, XmtpError};
+
+/// Default upper bound on a decompressed payload (10 MiB).
+///
+/// Decoding stops and fails once this many bytes have been inflated, so a
+/// small malicious message cannot expand into an arbitrarily large buffer.
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 10 * 1024 * 1024;
+
+/// When and how [`compress`] shrinks an encoded payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionOptions {
+    /// Algorithm to apply.
+    pub algorithm: Compression,
+    /// Only payloads of at least this many bytes are compressed.
+    pub threshold: usize,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: Compression::Deflate,
+            threshold: 1024,
+        }
+    }
+}
+
+impl EncodedContent {
+    /// Compress [`content`](Self::content) in place and record `algorithm`.
+    ///
+    /// Does nothing if the payload is already compressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`XmtpError::Io`] if the encoder fails.
+    pub fn compress(&mut self, algorithm: Compression) -> Result<()> {
+        if self.compression.is_some() {
+            return Ok(());
+        }
+        let level = flate2::Compression::default();
+        let out = match algorithm {
+            Compression::Deflate => {
+                let mut enc = ZlibEncoder::new(Vec::new(), level);
+                enc.write_all(&self.content).map_err(|e| io_err(&e))?;
+                enc.finish()
+            }
+            Compression::Gzip => {
+                let mut enc = GzEncoder::new(Vec::new(), level);
+                enc.write_all(&self.content).map_err(|e| io_err(&e))?;
+                enc.finish()
+            }
+        };
+        self.content = out.map_err(|e| io_err(&e))?;
+        self.compression = Some(algorithm as i32);
+        Ok(())
+    }
+
+    /// Inflate a compressed [`content`](Self::content) in place and clear
+    /// [`compression`](Self::compression). Uncompressed payloads are left as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the algorithm is unknown, the stream is corrupt, or
+    /// the output would exceed `max_len` bytes.
+    pub fn decompress(&mut self, max_len: usize) -> Result<()> {
+        let Some(raw) = self.compression else {
+            return Ok(());
+        };
+        let algorithm = Compression::try_from(raw)
+            .map_err(|_| XmtpError::Ffi(format!("unknown compression: {raw}")))?;
+        let input = self.content.as_slice();
+        self.content = match algorithm {
+            Compression::Deflate => inflate(ZlibDecoder::new(input), max_len)?,
+            Compression::Gzip => inflate(GzDecoder::new(input), max_len)?,
+        };
+        self.compression = None;
+        Ok(())
+    }
+}
+
+/// Compress the payload of protobuf-encoded content, as returned by the
+/// `encode_*` helpers, if it reaches `opts.threshold`.
+///
+/// ```
+/// use xmtp::content::{self, CompressionOptions};
+///
+/// let big = "lorem ipsum ".repeat(500);
+/// let raw = content::compress(&content::encode_text(&big), &CompressionOptions::default())?;
+/// assert_eq!(content::decode(&raw)?.as_text(), Some(big.as_str()));
+/// # Ok::<(), xmtp::XmtpError>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `raw` is not valid `EncodedContent` or compression fails.
+pub fn compress(raw: &[u8], opts: &CompressionOptions) -> Result<Vec<u8>> {
+    let mut ec =
+        EncodedContent::decode(raw).map_err(|e| XmtpError::Ffi(format!("protobuf decode: {e}")))?;
+    if ec.content.len() < opts.threshold {
+        return Ok(raw.to_vec());
+    }
+    ec.compress(opts.algorithm)?;
+    Ok(ec.encode_to_vec())
+}
+
+/// Read `decoder` to the end, failing once more than `max_len` bytes come out.
+fn inflate(decoder: impl Read, max_len: usize) -> Result<Vec<u8>> {
+    let limit = u64::try_from(max_len).unwrap_or(u64::MAX).saturating_add(1);
+    let mut out = Vec::new();
+    decoder
+        .take(limit)
+        .read_to_end(&mut out)
+        .map_err(|e| XmtpError::Ffi(format!("decompress: {e}")))?;
+    if out.len() > max_len {
+        return Err(XmtpError::Ffi(format!(
+            "decompressed content exceeds {max_len} bytes"
+        )));
+    }
+    Ok(out)
+}
+
+fn io_err(e: &std::io::Error) -> XmtpError {
+    XmtpError::Io(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{decode, encode_text};
+
+    #[test]
+    fn round_trips_both_algorithms_and_skips_small_payloads() {
+        let text = "hello ".repeat(400);
+        for algorithm in [Compression::Deflate, Compression::Gzip] {
+            let opts = CompressionOptions {
+                algorithm,
+                threshold: 16,
+            };
+            let raw = compress(&encode_text(&text), &opts).unwrap();
+            let ec = EncodedContent::decode(raw.as_slice()).unwrap();
+            assert_eq!(ec.compression, Some(algorithm as i32));
+            assert!(ec.content.len() < text.len());
+            assert_eq!(decode(&raw).unwrap().as_text(), Some(text.as_str()));
+        }
+
+        let small = encode_text("hi");
+        assert_eq!(
+            compress(&small, &CompressionOptions::default()).unwrap(),
+            small
+        );
+    }
+
+    #[test]
+    fn decompression_stops_at_limit() {
+        let mut ec = EncodedContent {
+            content: vec![0; 4096],
+            ..Default::default()
+        };
+        ec.compress(Compression::Gzip).unwrap();
+        let mut bomb = ec.clone();
+        assert!(bomb.decompress(4095).is_err());
+        ec.decompress(4096).unwrap();
+        assert_eq!(ec.content.len(), 4096);
+        assert_eq!(ec.compression, None);
+    }
+}
@@ -0,0 +1,140 @@
+//! Group membership and metadata changes (`xmtp.org/group_updated:1.0`).
+//!
+//! These are the system messages with
+//! [`MessageKind::MembershipChange`](crate::MessageKind::MembershipChange)
+//! that the library writes when a group is modified.
+
+use prost::Message as ProstMessage;
+
+use crate::error::{Result, XmtpError};
+
+/// A decoded group change: who did it, membership deltas, and metadata edits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupUpdated {
+    /// Inbox ID of the member that made the change.
+    pub initiated_by: String,
+    /// Inbox IDs added to the group.
+    pub added_inboxes: Vec<String>,
+    /// Inbox IDs removed by another member.
+    pub removed_inboxes: Vec<String>,
+    /// Inbox IDs that left on their own.
+    pub left_inboxes: Vec<String>,
+    /// Metadata fields that changed, in order.
+    pub metadata_changes: Vec<MetadataChange>,
+}
+
+/// One metadata field edit in a [`GroupUpdated`] message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataChange {
+    /// Field name (e.g. `"group_name"`).
+    pub field: String,
+    /// Previous value, if the field was set.
+    pub old_value: Option<String>,
+    /// New value, if the field is still set.
+    pub new_value: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, ProstMessage)]
+struct GroupUpdatedProto {
+    #[prost(string, tag = "1")]
+    initiated_by_inbox_id: String,
+    #[prost(message, repeated, tag = "2")]
+    added_inboxes: Vec<InboxProto>,
+    #[prost(message, repeated, tag = "3")]
+    removed_inboxes: Vec<InboxProto>,
+    #[prost(message, repeated, tag = "4")]
+    metadata_field_changes: Vec<MetadataFieldChangeProto>,
+    #[prost(message, repeated, tag = "5")]
+    left_inboxes: Vec<InboxProto>,
+}
+
+#[derive(Clone, PartialEq, Eq, ProstMessage)]
+struct InboxProto {
+    #[prost(string, tag = "1")]
+    inbox_id: String,
+}
+
+#[derive(Clone, PartialEq, Eq, ProstMessage)]
+struct MetadataFieldChangeProto {
+    #[prost(string, tag = "1")]
+    field_name: String,
+    #[prost(string, optional, tag = "2")]
+    old_value: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    new_value: Option<String>,
+}
+
+fn inbox_ids(inboxes: Vec<InboxProto>) -> Vec<String> {
+    inboxes.into_iter().map(|i| i.inbox_id).collect()
+}
+
+/// Parse the protobuf body of a group update.
+pub(super) fn decode_group_updated(content: &[u8]) -> Result<GroupUpdated> {
+    let p = GroupUpdatedProto::decode(content)
+        .map_err(|e| XmtpError::Ffi(format!("group updated decode: {e}")))?;
+    Ok(GroupUpdated {
+        initiated_by: p.initiated_by_inbox_id,
+        added_inboxes: inbox_ids(p.added_inboxes),
+        removed_inboxes: inbox_ids(p.removed_inboxes),
+        left_inboxes: inbox_ids(p.left_inboxes),
+        metadata_changes: p
+            .metadata_field_changes
+            .into_iter()
+            .map(|c| MetadataChange {
+                field: c.field_name,
+                old_value: c.old_value,
+                new_value: c.new_value,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{ContentTypeId, EncodedContent, decode};
+
+    #[test]
+    fn decodes_membership_and_metadata_changes() {
+        let inbox = |id: &str| InboxProto {
+            inbox_id: id.into(),
+        };
+        let body = GroupUpdatedProto {
+            initiated_by_inbox_id: "alice".into(),
+            added_inboxes: vec![inbox("bob")],
+            removed_inboxes: vec![inbox("carol")],
+            metadata_field_changes: vec![MetadataFieldChangeProto {
+                field_name: "group_name".into(),
+                old_value: Some("X".into()),
+                new_value: Some("Y".into()),
+            }],
+            left_inboxes: vec![inbox("dave")],
+        };
+        let raw = EncodedContent {
+            r#type: Some(ContentTypeId {
+                authority_id: "xmtp.org".into(),
+                type_id: "group_updated".into(),
+                version_major: 1,
+                version_minor: 0,
+            }),
+            content: body.encode_to_vec(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+
+        let decoded = decode(&raw).unwrap();
+        let update = decoded.as_group_updated().unwrap();
+        assert_eq!(update.initiated_by, "alice");
+        assert_eq!(update.added_inboxes, ["bob"]);
+        assert_eq!(update.removed_inboxes, ["carol"]);
+        assert_eq!(update.left_inboxes, ["dave"]);
+        assert_eq!(
+            update.metadata_changes,
+            [MetadataChange {
+                field: "group_name".into(),
+                old_value: Some("X".into()),
+                new_value: Some("Y".into()),
+            }]
+        );
+    }
+}
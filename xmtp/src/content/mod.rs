@@ -3,12 +3,39 @@
 //! This module is available when the `content` feature is enabled (default).
 //! It provides type-safe wrappers around the raw protobuf `EncodedContent`
 //! wire format so callers never need to construct protobuf bytes manually.
+//! App-specific content types plug in through [`ContentCodec`] and
+//! [`CodecRegistry`]. Compressed payloads are inflated transparently on
+//! decode; see [`compress`] for the encode side. Encrypting, uploading, and
+//! downloading [`RemoteAttachment`] payloads needs the `remote-attachment`
+//! feature.
+
+mod actions;
+mod codec;
+mod compression;
+mod group_updated;
+mod multi_remote;
+#[cfg(feature = "remote-attachment")]
+mod remote;
+mod thread;
+mod transaction;
+mod type_id;
+mod wallet;
 
 use std::collections::BTreeMap;
 
+pub use actions::{Action, ActionStyle, Actions, Intent, encode_actions, encode_intent};
+pub use codec::{CodecRegistry, ContentCodec, CustomContent, encode_with};
+pub use compression::{CompressionOptions, DEFAULT_MAX_DECOMPRESSED_LEN, compress};
+pub use group_updated::{GroupUpdated, MetadataChange};
+pub use multi_remote::{MultiRemoteAttachment, encode_multi_remote_attachment};
 use prost::Message as ProstMessage;
+#[cfg(feature = "remote-attachment")]
+pub use remote::{AttachmentStore, EncryptedPayload, LocalAttachmentStore};
+pub use transaction::{TransactionMetadata, TransactionReference, encode_transaction_reference};
+pub use type_id::KnownType;
+pub use wallet::{CallMetadata, WalletCall, WalletSendCalls, encode_wallet_send_calls};
 
-use crate::conversation::{Conversation, Message};
+use crate::conversation::{Conversation, Message, MessagePreview, ProcessedMessage};
 use crate::error::Result;
 use crate::types::SendOptions;
 
@@ -135,31 +162,78 @@ pub struct RemoteAttachmentInfo {
 }
 
 const XMTP_ORG: &str = "xmtp.org";
+const COINBASE: &str = "coinbase.com";
 
-/// Create a [`ContentTypeId`] for a well-known XMTP content type.
-const fn xmtp_type(type_id: &'static str, major: u32) -> (&'static str, &'static str, u32, u32) {
-    (XMTP_ORG, type_id, major, 0)
-}
-
-fn make_type_id(t: (&str, &str, u32, u32)) -> ContentTypeId {
-    ContentTypeId {
-        authority_id: t.0.into(),
-        type_id: t.1.into(),
-        version_major: t.2,
-        version_minor: t.3,
+/// A well-known `xmtp.org` content type.
+const fn xmtp_type(type_id: &'static str, major: u32) -> KnownType {
+    KnownType {
+        authority_id: XMTP_ORG,
+        type_id,
+        version_major: major,
+        version_minor: 0,
     }
 }
 
-const TEXT: (&str, &str, u32, u32) = xmtp_type("text", 1);
-const MARKDOWN: (&str, &str, u32, u32) = xmtp_type("markdown", 1);
-const REACTION: (&str, &str, u32, u32) = xmtp_type("reaction", 2);
-const READ_RECEIPT: (&str, &str, u32, u32) = xmtp_type("readReceipt", 1);
-const REPLY: (&str, &str, u32, u32) = xmtp_type("reply", 1);
-const ATTACHMENT: (&str, &str, u32, u32) = xmtp_type("attachment", 1);
-const REMOTE_ATTACHMENT: (&str, &str, u32, u32) = xmtp_type("remoteStaticAttachment", 1);
+/// `xmtp.org/text:1.0`
+pub const TEXT: KnownType = xmtp_type("text", 1);
+/// `xmtp.org/markdown:1.0`
+pub const MARKDOWN: KnownType = xmtp_type("markdown", 1);
+/// `xmtp.org/reaction:2.0`
+pub const REACTION: KnownType = xmtp_type("reaction", 2);
+/// `xmtp.org/readReceipt:1.0`
+pub const READ_RECEIPT: KnownType = xmtp_type("readReceipt", 1);
+/// `xmtp.org/reply:1.0`
+pub const REPLY: KnownType = xmtp_type("reply", 1);
+/// `xmtp.org/attachment:1.0`
+pub const ATTACHMENT: KnownType = xmtp_type("attachment", 1);
+/// `xmtp.org/remoteStaticAttachment:1.0`
+pub const REMOTE_ATTACHMENT: KnownType = xmtp_type("remoteStaticAttachment", 1);
+/// `xmtp.org/multiRemoteStaticAttachment:1.0`
+pub const MULTI_REMOTE_ATTACHMENT: KnownType = xmtp_type("multiRemoteStaticAttachment", 1);
+/// `xmtp.org/group_updated:1.0`
+pub const GROUP_UPDATED: KnownType = xmtp_type("group_updated", 1);
+/// `xmtp.org/transactionReference:1.0`
+pub const TRANSACTION_REFERENCE: KnownType = xmtp_type("transactionReference", 1);
+/// `xmtp.org/walletSendCalls:1.0`
+pub const WALLET_SEND_CALLS: KnownType = xmtp_type("walletSendCalls", 1);
+/// `coinbase.com/actions:1.0`
+pub const ACTIONS: KnownType = KnownType {
+    authority_id: COINBASE,
+    type_id: "actions",
+    version_major: 1,
+    version_minor: 0,
+};
+/// `coinbase.com/intent:1.0`
+pub const INTENT: KnownType = KnownType {
+    authority_id: COINBASE,
+    type_id: "intent",
+    version_major: 1,
+    version_minor: 0,
+};
+
+/// Content types [`decode`] understands without a registered codec.
+const BUILTIN: [KnownType; 13] = [
+    TEXT,
+    MARKDOWN,
+    REACTION,
+    READ_RECEIPT,
+    REPLY,
+    ATTACHMENT,
+    REMOTE_ATTACHMENT,
+    MULTI_REMOTE_ATTACHMENT,
+    GROUP_UPDATED,
+    TRANSACTION_REFERENCE,
+    WALLET_SEND_CALLS,
+    ACTIONS,
+    INTENT,
+];
 
 /// Decoded message content.
+///
+/// New content types are added as variants over time, so matches need a
+/// wildcard arm.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Content {
     /// Plain text message.
     Text(String),
@@ -175,6 +249,20 @@ pub enum Content {
     Attachment(Attachment),
     /// Remote (URL-hosted) encrypted attachment.
     RemoteAttachment(RemoteAttachment),
+    /// Several remote attachments in one message.
+    MultiRemoteAttachment(MultiRemoteAttachment),
+    /// Group membership or metadata change (a system message).
+    GroupUpdated(GroupUpdated),
+    /// Reference to an on-chain transaction.
+    TransactionReference(TransactionReference),
+    /// Request to approve a batch of wallet calls.
+    WalletSendCalls(WalletSendCalls),
+    /// Buttons for the recipient to choose from.
+    Actions(Actions),
+    /// A choice made in response to [`Content::Actions`].
+    Intent(Intent),
+    /// A type decoded by a registered [`ContentCodec`].
+    Custom(CustomContent),
     /// Unknown or unsupported content type.
     Unknown {
         /// The content type string (e.g. `"xmtp.org/text:1.0"`).
@@ -227,6 +315,48 @@ pub const fn is_remote_attachment(&self) -> bool {
         matches!(self, Self::RemoteAttachment(_))
     }
 
+    /// Returns `true` if this is a [`Content::MultiRemoteAttachment`].
+    #[must_use]
+    pub const fn is_multi_remote_attachment(&self) -> bool {
+        matches!(self, Self::MultiRemoteAttachment(_))
+    }
+
+    /// Returns `true` if this is a [`Content::GroupUpdated`].
+    #[must_use]
+    pub const fn is_group_updated(&self) -> bool {
+        matches!(self, Self::GroupUpdated(_))
+    }
+
+    /// Returns `true` if this is a [`Content::TransactionReference`].
+    #[must_use]
+    pub const fn is_transaction_reference(&self) -> bool {
+        matches!(self, Self::TransactionReference(_))
+    }
+
+    /// Returns `true` if this is a [`Content::WalletSendCalls`].
+    #[must_use]
+    pub const fn is_wallet_send_calls(&self) -> bool {
+        matches!(self, Self::WalletSendCalls(_))
+    }
+
+    /// Returns `true` if this is a [`Content::Actions`].
+    #[must_use]
+    pub const fn is_actions(&self) -> bool {
+        matches!(self, Self::Actions(_))
+    }
+
+    /// Returns `true` if this is a [`Content::Intent`].
+    #[must_use]
+    pub const fn is_intent(&self) -> bool {
+        matches!(self, Self::Intent(_))
+    }
+
+    /// Returns `true` if this is a [`Content::Custom`].
+    #[must_use]
+    pub const fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+
     /// Returns `true` if this is a [`Content::Unknown`].
     #[must_use]
     pub const fn is_unknown(&self) -> bool {
@@ -283,6 +413,81 @@ pub const fn as_remote_attachment(&self) -> Option<&RemoteAttachment> {
             None
         }
     }
+
+    /// Returns the attachments if this is a
+    /// [`Content::MultiRemoteAttachment`], or `None`.
+    #[must_use]
+    pub const fn as_multi_remote_attachment(&self) -> Option<&MultiRemoteAttachment> {
+        if let Self::MultiRemoteAttachment(m) = self {
+            Some(m)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the group change if this is a [`Content::GroupUpdated`], or
+    /// `None`.
+    #[must_use]
+    pub const fn as_group_updated(&self) -> Option<&GroupUpdated> {
+        if let Self::GroupUpdated(g) = self {
+            Some(g)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the transaction reference if this is a
+    /// [`Content::TransactionReference`], or `None`.
+    #[must_use]
+    pub const fn as_transaction_reference(&self) -> Option<&TransactionReference> {
+        if let Self::TransactionReference(t) = self {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the call request if this is a [`Content::WalletSendCalls`], or
+    /// `None`.
+    #[must_use]
+    pub const fn as_wallet_send_calls(&self) -> Option<&WalletSendCalls> {
+        if let Self::WalletSendCalls(w) = self {
+            Some(w)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the actions if this is a [`Content::Actions`], or `None`.
+    #[must_use]
+    pub const fn as_actions(&self) -> Option<&Actions> {
+        if let Self::Actions(a) = self {
+            Some(a)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the intent if this is a [`Content::Intent`], or `None`.
+    #[must_use]
+    pub const fn as_intent(&self) -> Option<&Intent> {
+        if let Self::Intent(i) = self {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the codec value if this is a [`Content::Custom`] holding a `T`,
+    /// or `None`.
+    #[must_use]
+    pub fn as_custom<T: std::any::Any>(&self) -> Option<&T> {
+        if let Self::Custom(c) = self {
+            c.downcast_ref()
+        } else {
+            None
+        }
+    }
 }
 
 /// A decoded reaction.
@@ -307,10 +512,33 @@ pub struct Reply {
     pub reference: String,
     /// Inbox ID of the referenced message's sender.
     pub reference_inbox_id: Option<String>,
-    /// The reply content (protobuf-encoded inner `EncodedContent`).
+    /// The reply body as an envelope; decode it with [`inner`](Self::inner).
     pub content: EncodedContent,
 }
 
+impl Reply {
+    /// Decode the reply body like a top-level message, including nested
+    /// replies and compressed payloads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is corrupt or inflates beyond
+    /// [`DEFAULT_MAX_DECOMPRESSED_LEN`].
+    pub fn inner(&self) -> Result<Content> {
+        decode(&self.content.encode_to_vec())
+    }
+
+    /// Decode the reply body with `registry`, so registered custom types come
+    /// back as [`Content::Custom`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is corrupt or the codec rejects it.
+    pub fn inner_with(&self, registry: &CodecRegistry) -> Result<Content> {
+        registry.decode(&self.content.encode_to_vec())
+    }
+}
+
 /// An inline file attachment.
 #[derive(Debug, Clone)]
 pub struct Attachment {
@@ -323,6 +551,9 @@ pub struct Attachment {
 }
 
 /// A remote (URL-hosted) encrypted attachment.
+///
+/// With the `remote-attachment` feature, create one with `encrypt` or
+/// `upload` and recover the file with `decrypt` or `download`.
 #[derive(Debug, Clone)]
 pub struct RemoteAttachment {
     /// URL of the encrypted payload.
@@ -347,7 +578,7 @@ pub struct RemoteAttachment {
 #[must_use]
 pub fn encode_text(text: &str) -> Vec<u8> {
     EncodedContent {
-        r#type: Some(make_type_id(TEXT)),
+        r#type: Some(TEXT.id()),
         parameters: BTreeMap::from([("encoding".into(), "UTF-8".into())]),
         fallback: None,
         content: text.as_bytes().to_vec(),
@@ -360,7 +591,7 @@ pub fn encode_text(text: &str) -> Vec<u8> {
 #[must_use]
 pub fn encode_markdown(markdown: &str) -> Vec<u8> {
     EncodedContent {
-        r#type: Some(make_type_id(MARKDOWN)),
+        r#type: Some(MARKDOWN.id()),
         parameters: BTreeMap::from([("encoding".into(), "UTF-8".into())]),
         fallback: None,
         content: markdown.as_bytes().to_vec(),
@@ -380,7 +611,7 @@ pub fn encode_reaction(reference: &str, emoji: &str, action: ReactionAction) ->
         schema: ReactionSchema::Unicode as i32,
     };
     EncodedContent {
-        r#type: Some(make_type_id(REACTION)),
+        r#type: Some(REACTION.id()),
         parameters: BTreeMap::new(),
         fallback: Some(format!("Reacted with \"{emoji}\" to an earlier message")),
         content: rv2.encode_to_vec(),
@@ -393,7 +624,7 @@ pub fn encode_reaction(reference: &str, emoji: &str, action: ReactionAction) ->
 #[must_use]
 pub fn encode_read_receipt() -> Vec<u8> {
     EncodedContent {
-        r#type: Some(make_type_id(READ_RECEIPT)),
+        r#type: Some(READ_RECEIPT.id()),
         parameters: BTreeMap::new(),
         fallback: None,
         content: Vec::new(),
@@ -409,7 +640,7 @@ pub fn encode_read_receipt() -> Vec<u8> {
 #[must_use]
 pub fn encode_reply(reference: &str, inner_content: &[u8]) -> Vec<u8> {
     EncodedContent {
-        r#type: Some(make_type_id(REPLY)),
+        r#type: Some(REPLY.id()),
         parameters: BTreeMap::from([("reference".into(), reference.into())]),
         fallback: Some("Replied to an earlier message".into()),
         content: inner_content.to_vec(),
@@ -436,7 +667,7 @@ pub fn encode_attachment(attachment: &Attachment) -> Vec<u8> {
         attachment.filename.as_deref().unwrap_or("this content")
     ));
     EncodedContent {
-        r#type: Some(make_type_id(ATTACHMENT)),
+        r#type: Some(ATTACHMENT.id()),
         parameters: params,
         fallback,
         content: attachment.data.clone(),
@@ -469,7 +700,7 @@ pub fn encode_remote_attachment(ra: &RemoteAttachment) -> Vec<u8> {
         ra.filename.as_deref().unwrap_or("this content")
     ));
     EncodedContent {
-        r#type: Some(make_type_id(REMOTE_ATTACHMENT)),
+        r#type: Some(REMOTE_ATTACHMENT.id()),
         parameters: params,
         fallback,
         content: ra.url.as_bytes().to_vec(),
@@ -480,27 +711,50 @@ pub fn encode_remote_attachment(ra: &RemoteAttachment) -> Vec<u8> {
 
 /// Decode raw `Message::content` bytes into a [`Content`] variant.
 ///
+/// Only built-in content types are recognized; use
+/// [`CodecRegistry::decode`] to include registered codecs.
+///
 /// # Errors
 ///
-/// Returns an error if the bytes cannot be parsed as protobuf `EncodedContent`.
+/// Returns an error if the bytes cannot be parsed as protobuf `EncodedContent`,
+/// or if a compressed payload is corrupt or inflates beyond
+/// [`DEFAULT_MAX_DECOMPRESSED_LEN`].
 pub fn decode(raw: &[u8]) -> Result<Content> {
-    let ec = EncodedContent::decode(raw)
-        .map_err(|e| crate::XmtpError::Ffi(format!("protobuf decode: {e}")))?;
+    decode_builtin(parse(raw, DEFAULT_MAX_DECOMPRESSED_LEN)?, raw)
+}
 
-    let type_id = ec.r#type.as_ref().map(|t| t.type_id.as_str());
+/// Parse an envelope and inflate its payload, allowing at most `max_len`
+/// decompressed bytes.
+fn parse(raw: &[u8], max_len: usize) -> Result<EncodedContent> {
+    let mut ec = EncodedContent::decode(raw)
+        .map_err(|e| crate::XmtpError::Ffi(format!("protobuf decode: {e}")))?;
+    ec.decompress(max_len)?;
+    Ok(ec)
+}
 
-    match type_id {
-        Some("text") => {
+/// Decode a parsed envelope of a built-in type; anything else is
+/// [`Content::Unknown`] carrying `raw`.
+///
+/// A built-in type name with an unsupported major version is also treated as
+/// unknown rather than decoded with an incompatible layout.
+fn decode_builtin(ec: EncodedContent, raw: &[u8]) -> Result<Content> {
+    let known = ec
+        .r#type
+        .as_ref()
+        .and_then(|t| BUILTIN.into_iter().find(|k| k.is_compatible(t)));
+
+    match known {
+        Some(TEXT) => {
             let s = String::from_utf8(ec.content)
                 .map_err(|e| crate::XmtpError::Ffi(format!("invalid UTF-8 text: {e}")))?;
             Ok(Content::Text(s))
         }
-        Some("markdown") => {
+        Some(MARKDOWN) => {
             let s = String::from_utf8(ec.content)
                 .map_err(|e| crate::XmtpError::Ffi(format!("invalid UTF-8 markdown: {e}")))?;
             Ok(Content::Markdown(s))
         }
-        Some("reaction") => {
+        Some(REACTION) => {
             let rv2 = ReactionV2::decode(ec.content.as_slice())
                 .map_err(|e| crate::XmtpError::Ffi(format!("reaction decode: {e}")))?;
             Ok(Content::Reaction(Reaction {
@@ -511,8 +765,8 @@ pub fn decode(raw: &[u8]) -> Result<Content> {
                 schema: ReactionSchema::try_from(rv2.schema).unwrap_or(ReactionSchema::Unspecified),
             }))
         }
-        Some("readReceipt") => Ok(Content::ReadReceipt),
-        Some("attachment") => {
+        Some(READ_RECEIPT) => Ok(Content::ReadReceipt),
+        Some(ATTACHMENT) => {
             let mime_type = ec.parameters.get("mimeType").cloned().unwrap_or_default();
             let filename = ec.parameters.get("filename").cloned();
             Ok(Content::Attachment(Attachment {
@@ -521,7 +775,7 @@ pub fn decode(raw: &[u8]) -> Result<Content> {
                 data: ec.content,
             }))
         }
-        Some("remoteStaticAttachment") => {
+        Some(REMOTE_ATTACHMENT) => {
             let content_digest = ec
                 .parameters
                 .get("contentDigest")
@@ -561,8 +815,23 @@ pub fn decode(raw: &[u8]) -> Result<Content> {
                 filename,
             }))
         }
-        Some("reply") => {
-            let inner = EncodedContent::decode(ec.content.as_slice()).unwrap_or_default();
+        Some(MULTI_REMOTE_ATTACHMENT) => Ok(Content::MultiRemoteAttachment(
+            multi_remote::decode_multi_remote_attachment(&ec.content)?,
+        )),
+        Some(GROUP_UPDATED) => Ok(Content::GroupUpdated(group_updated::decode_group_updated(
+            &ec.content,
+        )?)),
+        Some(TRANSACTION_REFERENCE) => Ok(Content::TransactionReference(
+            transaction::decode_transaction_reference(&ec.content)?,
+        )),
+        Some(WALLET_SEND_CALLS) => Ok(Content::WalletSendCalls(wallet::decode_wallet_send_calls(
+            &ec.content,
+        )?)),
+        Some(ACTIONS) => Ok(Content::Actions(actions::decode_actions(&ec.content)?)),
+        Some(INTENT) => Ok(Content::Intent(actions::decode_intent(&ec.content)?)),
+        Some(REPLY) => {
+            let inner = EncodedContent::decode(ec.content.as_slice())
+                .map_err(|e| crate::XmtpError::Ffi(format!("reply decode: {e}")))?;
             let reference = ec.parameters.get("reference").cloned().unwrap_or_default();
             let reference_inbox_id = ec.parameters.get("referenceInboxId").cloned();
             Ok(Content::Reply(Reply {
@@ -571,22 +840,49 @@ pub fn decode(raw: &[u8]) -> Result<Content> {
                 content: inner,
             }))
         }
-        _ => {
-            let ct = ec.r#type.as_ref().map_or_else(String::new, |t| {
-                format!(
-                    "{}/{}:{}.{}",
-                    t.authority_id, t.type_id, t.version_major, t.version_minor
-                )
-            });
-            Ok(Content::Unknown {
-                content_type: ct,
-                raw: raw.to_vec(),
-            })
-        }
+        _ => Ok(Content::Unknown {
+            content_type: ec
+                .r#type
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            raw: raw.to_vec(),
+        }),
     }
 }
 
 impl Message {
+    /// Decode the raw content bytes into a typed [`Content`] variant.
+    ///
+    /// **Only built-in content types are recognized.** A message has no link
+    /// to the client that fetched it, so codecs registered with
+    /// [`ClientBuilder::codec`](crate::ClientBuilder::codec) are not consulted
+    /// and their types decode as [`Content::Unknown`]. Use
+    /// [`Client::decode`](crate::Client::decode),
+    /// [`Client::decoded_messages`](crate::Client::decoded_messages), or a
+    /// decoded stream such as
+    /// [`stream::decoded_messages`](crate::stream::decoded_messages) instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the protobuf bytes are malformed.
+    pub fn decode(&self) -> Result<Content> {
+        decode(&self.content)
+    }
+}
+
+impl MessagePreview {
+    /// Decode the raw content bytes into a typed [`Content`] variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the protobuf bytes are malformed.
+    pub fn decode(&self) -> Result<Content> {
+        decode(&self.content)
+    }
+}
+
+impl ProcessedMessage {
     /// Decode the raw content bytes into a typed [`Content`] variant.
     ///
     /// # Errors
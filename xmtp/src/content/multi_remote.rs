@@ -0,0 +1,118 @@
+//! Albums of remote attachments (`xmtp.org/multiRemoteStaticAttachment:1.0`).
+
+use std::collections::BTreeMap;
+
+use prost::Message as ProstMessage;
+
+use super::{EncodedContent, MULTI_REMOTE_ATTACHMENT, RemoteAttachment, RemoteAttachmentInfo};
+use crate::conversation::Conversation;
+use crate::error::{Result, XmtpError};
+
+/// Several remote attachments sent as one message (e.g. a photo album).
+#[derive(Debug, Clone, Default)]
+pub struct MultiRemoteAttachment {
+    /// The attachments, in display order.
+    pub attachments: Vec<RemoteAttachment>,
+}
+
+/// Protobuf body of a multi remote attachment.
+#[derive(Clone, PartialEq, Eq, ProstMessage)]
+struct MultiRemoteAttachmentProto {
+    #[prost(message, repeated, tag = "1")]
+    attachments: Vec<RemoteAttachmentInfo>,
+}
+
+impl From<&RemoteAttachment> for RemoteAttachmentInfo {
+    fn from(ra: &RemoteAttachment) -> Self {
+        Self {
+            content_digest: ra.content_digest.clone(),
+            secret: ra.secret.clone(),
+            nonce: ra.nonce.clone(),
+            salt: ra.salt.clone(),
+            scheme: ra.scheme.clone(),
+            url: ra.url.clone(),
+            content_length: ra.content_length,
+            filename: ra.filename.clone(),
+        }
+    }
+}
+
+impl From<RemoteAttachmentInfo> for RemoteAttachment {
+    fn from(info: RemoteAttachmentInfo) -> Self {
+        Self {
+            url: info.url,
+            content_digest: info.content_digest,
+            secret: info.secret,
+            nonce: info.nonce,
+            salt: info.salt,
+            scheme: info.scheme,
+            content_length: info.content_length,
+            filename: info.filename,
+        }
+    }
+}
+
+/// Encode a multi remote attachment into protobuf bytes.
+#[must_use]
+pub fn encode_multi_remote_attachment(multi: &MultiRemoteAttachment) -> Vec<u8> {
+    let body = MultiRemoteAttachmentProto {
+        attachments: multi.attachments.iter().map(Into::into).collect(),
+    };
+    EncodedContent {
+        r#type: Some(MULTI_REMOTE_ATTACHMENT.id()),
+        parameters: BTreeMap::new(),
+        fallback: Some(
+            "Can't display this content. This app doesn't support multiple remote attachments."
+                .into(),
+        ),
+        content: body.encode_to_vec(),
+        compression: None,
+    }
+    .encode_to_vec()
+}
+
+/// Parse the protobuf body of a multi remote attachment.
+pub(super) fn decode_multi_remote_attachment(content: &[u8]) -> Result<MultiRemoteAttachment> {
+    let body = MultiRemoteAttachmentProto::decode(content)
+        .map_err(|e| XmtpError::Ffi(format!("multi remote attachment decode: {e}")))?;
+    Ok(MultiRemoteAttachment {
+        attachments: body.attachments.into_iter().map(Into::into).collect(),
+    })
+}
+
+impl Conversation {
+    /// Send several remote attachments as one message.
+    pub fn send_multi_remote_attachment(&self, multi: &MultiRemoteAttachment) -> Result<String> {
+        self.send(&encode_multi_remote_attachment(multi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::decode;
+
+    #[test]
+    fn round_trips_every_attachment() {
+        let item = |n: u32| RemoteAttachment {
+            url: format!("https://example.com/{n}"),
+            content_digest: format!("{n:064x}"),
+            secret: vec![1; 32],
+            nonce: vec![2; 12],
+            salt: vec![3; 32],
+            scheme: "https://".into(),
+            content_length: Some(n),
+            filename: Some(format!("{n}.jpg")),
+        };
+        let multi = MultiRemoteAttachment {
+            attachments: vec![item(1), item(2)],
+        };
+        let decoded = decode(&encode_multi_remote_attachment(&multi)).unwrap();
+        let got = decoded.as_multi_remote_attachment().unwrap();
+        assert_eq!(got.attachments.len(), 2);
+        let second = got.attachments.get(1).unwrap();
+        assert_eq!(second.url, "https://example.com/2");
+        assert_eq!(second.nonce, vec![2; 12]);
+        assert_eq!(second.filename.as_deref(), Some("2.jpg"));
+    }
+}
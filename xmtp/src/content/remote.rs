@@ -0,0 +1,252 @@
+//! Encryption and storage for [`RemoteAttachment`] payloads.
+//!
+//! Follows the XMTP remote attachment scheme: the attachment is encoded as an
+//! [`EncodedContent`](super::EncodedContent), encrypted with AES-256-GCM under
+//! a key derived by HKDF-SHA256 from a random 32-byte secret and salt, and
+//! identified by the hex SHA-256 digest of the ciphertext.
+
+use std::fs;
+use std::path::PathBuf;
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+
+use super::{Attachment, Content, RemoteAttachment, decode, encode_attachment};
+use crate::error::{Result, XmtpError};
+
+const SECRET_LEN: usize = 32;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Ciphertext produced by [`RemoteAttachment::encrypt`], ready to upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedPayload {
+    /// Encrypted bytes to host at the attachment URL.
+    pub payload: Vec<u8>,
+    /// Hex SHA-256 digest of [`payload`](Self::payload).
+    pub content_digest: String,
+}
+
+/// Where encrypted attachment payloads are hosted.
+///
+/// Implement this over your blob storage (S3, IPFS, …) and use it with
+/// [`RemoteAttachment::upload`] and [`RemoteAttachment::download`].
+pub trait AttachmentStore {
+    /// Store `payload` and return the URL it can be fetched from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload cannot be stored.
+    fn upload(&self, payload: &EncryptedPayload) -> Result<String>;
+
+    /// Fetch the encrypted bytes stored at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is not served by this store or the read fails.
+    fn fetch(&self, url: &str) -> Result<Vec<u8>>;
+}
+
+impl RemoteAttachment {
+    /// Encrypt `attachment` with fresh key material.
+    ///
+    /// The returned [`RemoteAttachment`] carries everything but the location:
+    /// upload the payload, then set [`url`](Self::url) and
+    /// [`scheme`](Self::scheme) before sending it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system RNG or the cipher fails.
+    pub fn encrypt(attachment: &Attachment) -> Result<(EncryptedPayload, Self)> {
+        let secret = random::<SECRET_LEN>()?;
+        let salt = random::<SALT_LEN>()?;
+        let nonce = random::<NONCE_LEN>()?;
+        let payload = cipher(&secret, &salt)?
+            .encrypt(
+                &Nonce::from(nonce),
+                encode_attachment(attachment).as_slice(),
+            )
+            .map_err(|e| XmtpError::InvalidArgument(format!("attachment encrypt: {e}")))?;
+        let content_digest = hex::encode(Sha256::digest(&payload));
+        let remote = Self {
+            url: String::new(),
+            content_digest: content_digest.clone(),
+            secret: secret.to_vec(),
+            nonce: nonce.to_vec(),
+            salt: salt.to_vec(),
+            scheme: String::new(),
+            content_length: u32::try_from(payload.len()).ok(),
+            filename: attachment.filename.clone(),
+        };
+        Ok((
+            EncryptedPayload {
+                payload,
+                content_digest,
+            },
+            remote,
+        ))
+    }
+
+    /// Verify `payload` against [`content_digest`](Self::content_digest) and
+    /// decrypt it back into the original attachment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`XmtpError::InvalidArgument`] if the digest does not match,
+    /// the key material is malformed, or decryption fails.
+    pub fn decrypt(&self, payload: &[u8]) -> Result<Attachment> {
+        let digest = hex::encode(Sha256::digest(payload));
+        if !digest.eq_ignore_ascii_case(&self.content_digest) {
+            return Err(XmtpError::InvalidArgument(
+                "attachment digest mismatch".into(),
+            ));
+        }
+        let nonce: [u8; NONCE_LEN] = self.nonce.as_slice().try_into().map_err(|_| {
+            XmtpError::InvalidArgument(format!("attachment nonce must be {NONCE_LEN} bytes"))
+        })?;
+        let plaintext = cipher(&self.secret, &self.salt)?
+            .decrypt(&Nonce::from(nonce), payload)
+            .map_err(|e| XmtpError::InvalidArgument(format!("attachment decrypt: {e}")))?;
+        match decode(&plaintext)? {
+            Content::Attachment(a) => Ok(a),
+            _ => Err(XmtpError::InvalidArgument(
+                "remote payload is not an attachment".into(),
+            )),
+        }
+    }
+
+    /// Encrypt `attachment`, upload it to `store`, and return the metadata
+    /// to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encryption or the upload fails.
+    pub fn upload(attachment: &Attachment, store: &impl AttachmentStore) -> Result<Self> {
+        let (payload, mut remote) = Self::encrypt(attachment)?;
+        remote.url = store.upload(&payload)?;
+        remote.scheme = remote
+            .url
+            .split_once("://")
+            .map(|(scheme, _)| format!("{scheme}://"))
+            .unwrap_or_default();
+        Ok(remote)
+    }
+
+    /// Fetch the payload from `store` and decrypt it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fetch fails or [`decrypt`](Self::decrypt) rejects
+    /// the payload.
+    pub fn download(&self, store: &impl AttachmentStore) -> Result<Attachment> {
+        self.decrypt(&store.fetch(&self.url)?)
+    }
+}
+
+/// An [`AttachmentStore`] backed by a local directory, serving `file://` URLs.
+///
+/// Payloads are stored under their content digest. Intended for tests and
+/// local tooling.
+#[derive(Debug, Clone)]
+pub struct LocalAttachmentStore {
+    dir: PathBuf,
+}
+
+impl LocalAttachmentStore {
+    /// A store writing into `dir` (created on first upload).
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The `file://` URL of the store directory, with a trailing slash.
+    fn url_prefix(&self) -> Result<String> {
+        let dir = std::path::absolute(&self.dir).map_err(|e| XmtpError::Io(e.to_string()))?;
+        url::Url::from_directory_path(&dir)
+            .map(String::from)
+            .map_err(|()| {
+                XmtpError::InvalidArgument(format!("not a directory path: {}", dir.display()))
+            })
+    }
+}
+
+impl AttachmentStore for LocalAttachmentStore {
+    fn upload(&self, payload: &EncryptedPayload) -> Result<String> {
+        fs::create_dir_all(&self.dir).map_err(|e| XmtpError::Io(e.to_string()))?;
+        fs::write(self.dir.join(&payload.content_digest), &payload.payload)
+            .map_err(|e| XmtpError::Io(e.to_string()))?;
+        Ok(format!("{}{}", self.url_prefix()?, payload.content_digest))
+    }
+
+    fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+        // Only serve digests this store wrote; never follow arbitrary paths.
+        let prefix = self.url_prefix()?;
+        let name = url
+            .strip_prefix(&prefix)
+            .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| XmtpError::InvalidArgument(format!("not a local attachment: {url}")))?;
+        fs::read(self.dir.join(name)).map_err(|e| XmtpError::Io(e.to_string()))
+    }
+}
+
+fn cipher(secret: &[u8], salt: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), secret)
+        .expand(&[], &mut key)
+        .map_err(|e| XmtpError::InvalidArgument(format!("attachment key: {e}")))?;
+    Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| XmtpError::InvalidArgument(format!("attachment key: {e}")))
+}
+
+fn random<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    getrandom::fill(&mut bytes).map_err(|e| XmtpError::Io(format!("rng: {e}")))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Attachment {
+        Attachment {
+            filename: Some("hello.txt".into()),
+            mime_type: "text/plain".into(),
+            data: b"hello world".to_vec(),
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip_and_tamper_detection() {
+        let (encrypted, remote) = RemoteAttachment::encrypt(&sample()).unwrap();
+        assert_eq!(remote.content_digest, encrypted.content_digest);
+        assert_eq!(remote.secret.len(), SECRET_LEN);
+
+        let decrypted = remote.decrypt(&encrypted.payload).unwrap();
+        assert_eq!(decrypted.data, b"hello world");
+        assert_eq!(decrypted.filename.as_deref(), Some("hello.txt"));
+
+        let mut tampered = encrypted.payload;
+        if let Some(b) = tampered.first_mut() {
+            *b ^= 1;
+        }
+        assert!(remote.decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn local_store_uploads_and_downloads() {
+        let dir = std::env::temp_dir().join(format!("xmtp-attachments-{}", std::process::id()));
+        let store = LocalAttachmentStore::new(&dir);
+        let remote = RemoteAttachment::upload(&sample(), &store).unwrap();
+        assert_eq!(remote.scheme, "file://");
+        let url = url::Url::parse(&remote.url).unwrap();
+        assert_eq!(
+            url.to_file_path().ok(),
+            Some(dir.join(&remote.content_digest))
+        );
+        assert_eq!(remote.download(&store).unwrap().mime_type, "text/plain");
+        assert!(store.fetch("file:///etc/passwd").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
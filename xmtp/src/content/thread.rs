@@ -0,0 +1,122 @@
+//! Reconstructing reply threads from a conversation's history.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Content;
+use crate::conversation::{Conversation, Message};
+use crate::error::Result;
+use crate::types::{ListMessagesOptions, MessageKind, SortDirection};
+
+impl Conversation {
+    /// The thread containing `message_id`: its root message followed by every
+    /// direct and nested reply, oldest first.
+    ///
+    /// `message_id` may be the root or any reply in the thread. Returns an
+    /// empty list if the message is not in this conversation.
+    ///
+    /// This loads and decodes the conversation's entire history, so its cost
+    /// grows with the conversation. Use [`thread_with`](Self::thread_with) to
+    /// bound the scan in long-lived conversations.
+    pub fn thread(&self, message_id: &str) -> Result<Vec<Message>> {
+        self.thread_with(message_id, &ListMessagesOptions::default())
+    }
+
+    /// Like [`thread`](Self::thread), but only scans messages selected by
+    /// `opts`, e.g. a `sent_after_ns` window around the message.
+    ///
+    /// `direction` and `kind` are ignored: messages are always read oldest
+    /// first and only application messages are considered. Ancestors outside
+    /// the window are not found, so the thread then starts at the oldest one
+    /// inside it.
+    pub fn thread_with(
+        &self,
+        message_id: &str,
+        opts: &ListMessagesOptions,
+    ) -> Result<Vec<Message>> {
+        let messages = self.list_messages(&ListMessagesOptions {
+            direction: Some(SortDirection::Ascending),
+            kind: Some(MessageKind::Application),
+            ..*opts
+        })?;
+        Ok(thread_of(messages, message_id))
+    }
+}
+
+/// Select the thread containing `message_id` from `messages`, keeping their
+/// order.
+fn thread_of(messages: Vec<Message>, message_id: &str) -> Vec<Message> {
+    let parents: BTreeMap<String, String> = messages
+        .iter()
+        .filter_map(|m| match m.decode() {
+            Ok(Content::Reply(r)) => Some((m.id.clone(), r.reference)),
+            _ => None,
+        })
+        .collect();
+    let known: BTreeSet<&str> = messages.iter().map(|m| m.id.as_str()).collect();
+    if !known.contains(message_id) {
+        return Vec::new();
+    }
+
+    // Walk up to the root, stopping at a missing parent or a cycle.
+    let mut root = message_id;
+    let mut seen = BTreeSet::from([root]);
+    while let Some(parent) = parents.get(root).map(String::as_str) {
+        if !known.contains(parent) || !seen.insert(parent) {
+            break;
+        }
+        root = parent;
+    }
+
+    let mut children: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (child, parent) in &parents {
+        children.entry(parent).or_default().push(child);
+    }
+    let mut members = BTreeSet::from([root.to_owned()]);
+    let mut queue = vec![root];
+    while let Some(id) = queue.pop() {
+        for &child in children.get(id).into_iter().flatten() {
+            if members.insert(child.to_owned()) {
+                queue.push(child);
+            }
+        }
+    }
+
+    messages
+        .into_iter()
+        .filter(|m| members.contains(&m.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{encode_text, encode_text_reply};
+    fn message(id: &str, content: Vec<u8>) -> Message {
+        Message::fixture(id, "s", content)
+    }
+
+    #[test]
+    fn collects_root_and_nested_replies_in_order() {
+        let history = vec![
+            message("root", encode_text("question")),
+            message("other", encode_text("unrelated")),
+            message("r1", encode_text_reply("root", "answer")),
+            message("r2", encode_text_reply("r1", "follow-up")),
+            message("x", encode_text_reply("other", "elsewhere")),
+        ];
+        let ids = |t: Vec<Message>| t.into_iter().map(|m| m.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(thread_of(history.clone(), "r2")), ["root", "r1", "r2"]);
+        assert_eq!(
+            ids(thread_of(history.clone(), "root")),
+            ["root", "r1", "r2"]
+        );
+        assert!(thread_of(history, "missing").is_empty());
+
+        let reply = message("r", encode_text_reply("root", "hi"))
+            .decode()
+            .unwrap();
+        let inner = reply.as_reply().unwrap().inner().unwrap();
+        assert_eq!(inner.as_text(), Some("hi"));
+    }
+}
@@ -0,0 +1,124 @@
+//! On-chain transaction references (`xmtp.org/transactionReference:1.0`).
+
+use std::collections::BTreeMap;
+
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{EncodedContent, TRANSACTION_REFERENCE};
+use crate::conversation::Conversation;
+use crate::error::{Result, XmtpError};
+
+/// A reference to an on-chain transaction, rendered by clients as a payment
+/// or transfer card.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReference {
+    /// Chain namespace (e.g. `"eip155"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Network ID (e.g. `"0x14a34"` or `"8453"`).
+    #[serde(deserialize_with = "string_or_number")]
+    pub network_id: String,
+    /// Transaction hash.
+    pub reference: String,
+    /// Optional details for display.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TransactionMetadata>,
+}
+
+/// Display details of a [`TransactionReference`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMetadata {
+    /// Kind of transaction (e.g. `"transfer"`).
+    pub transaction_type: String,
+    /// Currency symbol (e.g. `"USDC"`).
+    pub currency: String,
+    /// Amount in the currency's smallest unit.
+    pub amount: f64,
+    /// Decimal places of the currency.
+    pub decimals: u32,
+    /// Sender address.
+    pub from_address: String,
+    /// Recipient address.
+    pub to_address: String,
+}
+
+/// Encode a transaction reference into protobuf bytes.
+///
+/// # Errors
+///
+/// Returns an error if the reference cannot be serialized.
+pub fn encode_transaction_reference(tx: &TransactionReference) -> Result<Vec<u8>> {
+    let content = serde_json::to_vec(tx)
+        .map_err(|e| XmtpError::InvalidArgument(format!("transaction reference: {e}")))?;
+    Ok(EncodedContent {
+        r#type: Some(TRANSACTION_REFERENCE.id()),
+        parameters: BTreeMap::new(),
+        fallback: Some(format!(
+            "[Crypto transaction] Use a blockchain explorer to learn more using the transaction hash: {}",
+            tx.reference
+        )),
+        content,
+        compression: None,
+    }
+    .encode_to_vec())
+}
+
+/// Parse the JSON payload of a transaction reference.
+pub(super) fn decode_transaction_reference(content: &[u8]) -> Result<TransactionReference> {
+    serde_json::from_slice(content)
+        .map_err(|e| XmtpError::Ffi(format!("transaction reference decode: {e}")))
+}
+
+/// Accept the network ID as either a JSON string or number.
+fn string_or_number<'de, D: Deserializer<'de>>(de: D) -> std::result::Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Str(String),
+        Num(u64),
+    }
+    Ok(match Id::deserialize(de)? {
+        Id::Str(s) => s,
+        Id::Num(n) => n.to_string(),
+    })
+}
+
+impl Conversation {
+    /// Send a reference to an on-chain transaction.
+    pub fn send_transaction_reference(&self, tx: &TransactionReference) -> Result<String> {
+        self.send(&encode_transaction_reference(tx)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{Content, decode};
+
+    #[test]
+    fn round_trips_and_accepts_numeric_network_id() {
+        let tx = TransactionReference {
+            namespace: Some("eip155".into()),
+            network_id: "0x14a34".into(),
+            reference: "0xabc".into(),
+            metadata: Some(TransactionMetadata {
+                transaction_type: "transfer".into(),
+                currency: "USDC".into(),
+                amount: 100_000.0,
+                decimals: 6,
+                from_address: "0x1".into(),
+                to_address: "0x2".into(),
+            }),
+        };
+        let raw = encode_transaction_reference(&tx).unwrap();
+        assert!(matches!(decode(&raw).unwrap(), Content::TransactionReference(t) if t == tx));
+
+        let parsed =
+            decode_transaction_reference(br#"{"networkId":8453,"reference":"0xdef"}"#).unwrap();
+        assert_eq!(parsed.network_id, "8453");
+        assert_eq!(parsed.metadata, None);
+    }
+}
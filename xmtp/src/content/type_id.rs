@@ -0,0 +1,169 @@
+//! Parsing, formatting, and version negotiation for content type identifiers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::ContentTypeId;
+use crate::conversation::Message;
+use crate::error::XmtpError;
+
+impl ContentTypeId {
+    /// Build an identifier from its parts.
+    pub fn new(
+        authority_id: impl Into<String>,
+        type_id: impl Into<String>,
+        version_major: u32,
+        version_minor: u32,
+    ) -> Self {
+        Self {
+            authority_id: authority_id.into(),
+            type_id: type_id.into(),
+            version_major,
+            version_minor,
+        }
+    }
+
+    /// Whether `other` names the same content type, ignoring versions.
+    #[must_use]
+    pub fn same_type(&self, other: &Self) -> bool {
+        self.authority_id == other.authority_id && self.type_id == other.type_id
+    }
+
+    /// Whether content of type `other` can be decoded as this type: same
+    /// authority and type name, same major version. Minor versions are
+    /// backwards compatible.
+    #[must_use]
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.same_type(other) && self.version_major == other.version_major
+    }
+}
+
+/// Formats as `authority/type:major.minor`, e.g. `xmtp.org/text:1.0`.
+impl fmt::Display for ContentTypeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}:{}.{}",
+            self.authority_id, self.type_id, self.version_major, self.version_minor
+        )
+    }
+}
+
+/// Parses `authority/type:major.minor`; the minor version may be omitted.
+///
+/// ```
+/// use xmtp::content::{ContentTypeId, TEXT};
+///
+/// let id: ContentTypeId = "xmtp.org/text:1.2".parse()?;
+/// assert!(TEXT.is_compatible(&id));
+/// assert!(!TEXT.is_compatible(&"xmtp.org/text:2.0".parse()?));
+/// # Ok::<(), xmtp::XmtpError>(())
+/// ```
+impl FromStr for ContentTypeId {
+    type Err = XmtpError;
+
+    fn from_str(s: &str) -> Result<Self, XmtpError> {
+        let invalid = || XmtpError::InvalidArgument(format!("invalid content type: {s:?}"));
+        let (authority, rest) = s.split_once('/').ok_or_else(invalid)?;
+        let (type_id, version) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        if authority.is_empty() || type_id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self::new(
+            authority,
+            type_id,
+            major.parse().map_err(|_| invalid())?,
+            minor.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+/// A well-known content type, usable in `const` context.
+///
+/// Compare against parsed identifiers with [`same_type`](Self::same_type) and
+/// [`is_compatible`](Self::is_compatible), or convert with
+/// [`id`](Self::id).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KnownType {
+    /// Authority (e.g. `"xmtp.org"`).
+    pub authority_id: &'static str,
+    /// Type name (e.g. `"text"`).
+    pub type_id: &'static str,
+    /// Major version.
+    pub version_major: u32,
+    /// Minor version.
+    pub version_minor: u32,
+}
+
+impl KnownType {
+    /// The owned [`ContentTypeId`].
+    #[must_use]
+    pub fn id(self) -> ContentTypeId {
+        ContentTypeId::new(
+            self.authority_id,
+            self.type_id,
+            self.version_major,
+            self.version_minor,
+        )
+    }
+
+    /// Whether `id` names this content type, ignoring versions.
+    #[must_use]
+    pub fn same_type(self, id: &ContentTypeId) -> bool {
+        self.authority_id == id.authority_id && self.type_id == id.type_id
+    }
+
+    /// Whether content of type `id` can be decoded as this type (same major
+    /// version).
+    #[must_use]
+    pub fn is_compatible(self, id: &ContentTypeId) -> bool {
+        self.same_type(id) && self.version_major == id.version_major
+    }
+}
+
+impl From<KnownType> for ContentTypeId {
+    fn from(t: KnownType) -> Self {
+        t.id()
+    }
+}
+
+impl fmt::Display for KnownType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}:{}.{}",
+            self.authority_id, self.type_id, self.version_major, self.version_minor
+        )
+    }
+}
+
+impl Message {
+    /// The parsed [`content_type`](Self::content_type), if present and valid.
+    #[must_use]
+    pub fn content_type_id(&self) -> Option<ContentTypeId> {
+        self.content_type.as_deref()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{REACTION, TEXT};
+
+    #[test]
+    fn parses_formats_and_negotiates_versions() {
+        let id: ContentTypeId = "xmtp.org/reaction:2.1".parse().unwrap();
+        assert_eq!(id, ContentTypeId::new("xmtp.org", "reaction", 2, 1));
+        assert_eq!(id.to_string(), "xmtp.org/reaction:2.1");
+        assert!(REACTION.is_compatible(&id));
+        assert!(REACTION.same_type(&"xmtp.org/reaction:1".parse().unwrap()));
+        assert!(!REACTION.is_compatible(&"xmtp.org/reaction:1".parse().unwrap()));
+        assert!(!TEXT.same_type(&id));
+        assert_eq!(TEXT.to_string(), TEXT.id().to_string());
+
+        for bad in ["text", "xmtp.org/text", "/text:1.0", "xmtp.org/text:one"] {
+            assert!(bad.parse::<ContentTypeId>().is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! EIP-5792 wallet call requests (`xmtp.org/walletSendCalls:1.0`).
+
+use std::collections::BTreeMap;
+
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+
+use super::{EncodedContent, WALLET_SEND_CALLS};
+use crate::conversation::Conversation;
+use crate::error::{Result, XmtpError};
+
+/// A request for the recipient's wallet to sign and submit a batch of calls
+/// (the `wallet_sendCalls` parameters of EIP-5792).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSendCalls {
+    /// Request format version (e.g. `"1.0"`).
+    pub version: String,
+    /// Hex chain ID (e.g. `"0x2105"`).
+    pub chain_id: String,
+    /// Address expected to send the calls.
+    pub from: String,
+    /// Calls to execute, in order.
+    pub calls: Vec<WalletCall>,
+    /// Wallet capabilities requested by the sender (e.g. a paymaster
+    /// service), kept as raw JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<serde_json::Value>,
+    /// Other top-level fields (e.g. `"id"`, `"atomicRequired"`), kept so a
+    /// decoded request re-encodes unchanged.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// One call in a [`WalletSendCalls`] request. Numeric fields are hex strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletCall {
+    /// Target address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Call data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// Value in wei.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Gas limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<String>,
+    /// Human-readable description for the approval UI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CallMetadata>,
+}
+
+/// Display metadata of a [`WalletCall`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallMetadata {
+    /// What the call does (e.g. `"Send 10 USDC to vitalik.eth"`). Empty if
+    /// the sender left it out.
+    #[serde(default)]
+    pub description: String,
+    /// Kind of transaction (e.g. `"transfer"`). Empty if the sender left it
+    /// out.
+    #[serde(default)]
+    pub transaction_type: String,
+    /// Additional app-defined fields (e.g. `"amount"`, `"decimals"`), which
+    /// senders may set to any JSON value.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Encode a wallet call request into protobuf bytes.
+///
+/// # Errors
+///
+/// Returns an error if the request cannot be serialized.
+pub fn encode_wallet_send_calls(calls: &WalletSendCalls) -> Result<Vec<u8>> {
+    let content = serde_json::to_vec(calls)
+        .map_err(|e| XmtpError::InvalidArgument(format!("wallet send calls: {e}")))?;
+    let fallback = format!(
+        "[Transaction request generated]: {}",
+        String::from_utf8_lossy(&content)
+    );
+    Ok(EncodedContent {
+        r#type: Some(WALLET_SEND_CALLS.id()),
+        parameters: BTreeMap::new(),
+        fallback: Some(fallback),
+        content,
+        compression: None,
+    }
+    .encode_to_vec())
+}
+
+/// Parse the JSON payload of a wallet call request.
+pub(super) fn decode_wallet_send_calls(content: &[u8]) -> Result<WalletSendCalls> {
+    serde_json::from_slice(content)
+        .map_err(|e| XmtpError::Ffi(format!("wallet send calls decode: {e}")))
+}
+
+impl Conversation {
+    /// Ask the recipient's wallet to approve a batch of calls.
+    pub fn send_wallet_send_calls(&self, calls: &WalletSendCalls) -> Result<String> {
+        self.send(&encode_wallet_send_calls(calls)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{Content, decode};
+
+    #[test]
+    fn round_trips_with_extra_metadata() {
+        let calls = WalletSendCalls {
+            version: "1.0".into(),
+            chain_id: "0x2105".into(),
+            from: "0x1".into(),
+            calls: vec![WalletCall {
+                to: Some("0x2".into()),
+                data: Some("0xa9059cbb".into()),
+                metadata: Some(CallMetadata {
+                    description: "Send 10 USDC".into(),
+                    transaction_type: "transfer".into(),
+                    extra: BTreeMap::from([("currency".into(), serde_json::json!("USDC"))]),
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let raw = encode_wallet_send_calls(&calls).unwrap();
+        assert!(matches!(decode(&raw).unwrap(), Content::WalletSendCalls(c) if c == calls));
+
+        let json = serde_json::to_value(&calls).unwrap();
+        assert_eq!(json.pointer("/chainId").unwrap(), "0x2105");
+        assert_eq!(json.pointer("/calls/0/metadata/currency").unwrap(), "USDC");
+        assert!(json.pointer("/calls/0/value").is_none());
+    }
+
+    #[test]
+    fn decodes_numeric_metadata_from_other_senders() {
+        let payload = br#"{
+            "version": "1.0",
+            "chainId": "0x14a34",
+            "from": "0xabc",
+            "calls": [{
+                "to": "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "data": "0xa9059cbb",
+                "metadata": {
+                    "description": "Transfer 10 USDC on Base Sepolia",
+                    "transactionType": "transfer",
+                    "currency": "USDC",
+                    "amount": 10000000,
+                    "decimals": 6,
+                    "networkId": "base-sepolia"
+                }
+            }]
+        }"#;
+        let calls = decode_wallet_send_calls(payload).unwrap();
+        let meta = calls
+            .calls
+            .first()
+            .and_then(|c| c.metadata.as_ref())
+            .unwrap();
+        assert_eq!(meta.transaction_type, "transfer");
+        assert_eq!(
+            meta.extra.get("amount"),
+            Some(&serde_json::json!(10_000_000))
+        );
+        assert_eq!(meta.extra.get("decimals"), Some(&serde_json::json!(6)));
+        assert_eq!(meta.extra.get("currency"), Some(&serde_json::json!("USDC")));
+    }
+
+    #[test]
+    fn keeps_capabilities_and_unknown_fields_through_a_round_trip() {
+        let payload = serde_json::json!({
+            "version": "2.0.0",
+            "id": "req-1",
+            "chainId": "0x2105",
+            "from": "0xabc",
+            "atomicRequired": true,
+            "calls": [{
+                "to": "0x2",
+                "data": "0xa9059cbb",
+                "metadata": { "currency": "USDC" }
+            }],
+            "capabilities": {
+                "paymasterService": { "url": "https://paymaster.example" }
+            }
+        });
+        let calls = decode_wallet_send_calls(payload.to_string().as_bytes()).unwrap();
+        assert_eq!(
+            calls
+                .capabilities
+                .as_ref()
+                .and_then(|c| c.pointer("/paymasterService/url")),
+            Some(&serde_json::json!("https://paymaster.example"))
+        );
+        assert_eq!(calls.extra.get("id"), Some(&serde_json::json!("req-1")));
+        let meta = calls
+            .calls
+            .first()
+            .and_then(|c| c.metadata.as_ref())
+            .unwrap();
+        assert!(meta.description.is_empty());
+        assert!(meta.transaction_type.is_empty());
+
+        assert_eq!(serde_json::to_value(&calls).unwrap(), {
+            let mut expected = payload;
+            let expected_meta = expected.pointer_mut("/calls/0/metadata").unwrap();
+            expected_meta["description"] = "".into();
+            expected_meta["transactionType"] = "".into();
+            expected
+        });
+    }
+}
@@ -87,6 +87,29 @@ pub struct Message {
     pub num_replies: i32,
 }
 
+#[cfg(all(test, feature = "content"))]
+impl Message {
+    /// A published application message in conversation `c1`, for unit tests.
+    pub(crate) fn fixture(id: &str, sender: &str, content: Vec<u8>) -> Self {
+        Self {
+            id: id.into(),
+            conversation_id: "c1".into(),
+            sender_inbox_id: sender.into(),
+            sender_installation_id: String::new(),
+            sent_at_ns: 0,
+            inserted_at_ns: 0,
+            kind: MessageKind::Application,
+            delivery_status: DeliveryStatus::Published,
+            content_type: None,
+            fallback: None,
+            content,
+            expires_at_ns: 0,
+            num_reactions: 0,
+            num_replies: 0,
+        }
+    }
+}
+
 /// A member of a group conversation.
 #[derive(Debug, Clone)]
 pub struct GroupMember {
@@ -102,12 +125,104 @@ pub struct GroupMember {
     pub installation_ids: Vec<String>,
 }
 
+/// The last message of a listed conversation, as returned alongside the listing.
+#[derive(Debug, Clone)]
+pub struct MessagePreview {
+    /// Hex-encoded message ID.
+    pub id: String,
+    /// Sender's inbox ID.
+    pub sender_inbox_id: String,
+    /// Sent timestamp in nanoseconds.
+    pub sent_at_ns: i64,
+    /// Raw decrypted content bytes (protobuf-encoded `EncodedContent`).
+    pub content: Vec<u8>,
+    /// Fallback text for unsupported content types, read from the content
+    /// header (requires the `content` feature).
+    pub fallback: Option<String>,
+    /// Message kind, derived from the content type: libxmtp stores membership
+    /// changes as `xmtp.org/group_updated`. `None` without the `content`
+    /// feature or if the content header is unreadable.
+    pub kind: Option<MessageKind>,
+}
+
+/// A message stored by [`Conversation::process_envelope`], as libxmtp
+/// reports it.
+///
+/// This is not a full [`Message`]: libxmtp does not report the sender's
+/// installation, the insert time, or the content type here. Fetch the full
+/// message with [`Client::message_by_id`](crate::Client::message_by_id).
+#[derive(Debug, Clone)]
+pub struct ProcessedMessage {
+    /// Hex-encoded message ID.
+    pub id: String,
+    /// Hex-encoded group/conversation ID.
+    pub conversation_id: String,
+    /// Sender's inbox ID.
+    pub sender_inbox_id: String,
+    /// Sent timestamp in nanoseconds.
+    pub sent_at_ns: i64,
+    /// Message kind.
+    pub kind: MessageKind,
+    /// Delivery status.
+    pub delivery_status: DeliveryStatus,
+    /// Raw decrypted content bytes (protobuf-encoded `EncodedContent`).
+    pub content: Vec<u8>,
+}
+
+/// A conversation from [`Client::list_conversation_items`](crate::Client::list_conversation_items)
+/// together with its last-message preview and fork status.
+#[derive(Debug)]
+pub struct ConversationListItem {
+    /// The conversation handle.
+    pub conversation: Conversation,
+    /// Last message, if any.
+    pub last_message: Option<MessagePreview>,
+    /// Sent timestamp of the last message in nanoseconds (0 = no messages).
+    pub last_message_sent_at_ns: i64,
+    /// Whether the group's commit log has forked. `None` = unknown.
+    pub commit_log_forked: Option<bool>,
+}
+
+/// A message stored locally by [`Conversation::prepare`] but not yet published.
+///
+/// The message is visible in [`Conversation::messages`] with
+/// [`DeliveryStatus::Unpublished`] until [`publish`](Self::publish) succeeds.
+/// Publishing can be retried; the same message ID is reused each time.
+#[derive(Debug)]
+pub struct PreparedMessage<'a> {
+    conversation: &'a Conversation,
+    id: String,
+}
+
+impl PreparedMessage<'_> {
+    /// Hex-encoded ID of the stored message.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Publish this message to the network.
+    pub fn publish(&self) -> Result<()> {
+        self.conversation.publish_prepared(&self.id)
+    }
+}
+
 /// A conversation handle (DM or group).
 #[derive(Debug)]
 pub struct Conversation {
     handle: OwnedHandle<xmtp_sys::XmtpFfiConversation>,
 }
 
+// SAFETY: Shared references only ever reach libxmtp through `handle_ptr` and
+// `self.handle.as_ptr()`. Every `xmtp_conversation_*` and stream entry point
+// that takes a conversation takes `*const XmtpFfiConversation` and only
+// borrows it as `&FfiConversation`; the sole `*mut` entry point,
+// `xmtp_conversation_free`, runs from `OwnedHandle::drop`, which has
+// exclusive access. `FfiConversation` wraps an `xmtp_mls::groups::MlsGroup`:
+// a group ID, the shared client context, and `Arc`-held locks, all
+// `Send + Sync`, with the group state itself in libxmtp's database.
+unsafe impl Sync for Conversation {}
+
 impl Conversation {
     /// Wrap a raw FFI conversation pointer. Takes ownership.
     pub(crate) fn from_raw(ptr: *mut xmtp_sys::XmtpFfiConversation) -> Result<Self> {
@@ -307,6 +422,42 @@ fn send_inner(
         unsafe { take_c_string(out) }
     }
 
+    /// Store a message locally without publishing it.
+    ///
+    /// Returns a [`PreparedMessage`] that publishes exactly this message later.
+    pub fn prepare(&self, content: &[u8], opts: &SendOptions) -> Result<PreparedMessage<'_>> {
+        let len = to_ffi_len(content.len())?;
+        let mut out: *mut c_char = ptr::null_mut();
+        // SAFETY: Valid handle and content buffer; `out` receives the message ID.
+        let rc = unsafe {
+            xmtp_sys::xmtp_conversation_prepare_message(
+                self.handle.as_ptr(),
+                content.as_ptr(),
+                len,
+                i32::from(opts.should_push),
+                &raw mut out,
+            )
+        };
+        error::check(rc)?;
+        Ok(PreparedMessage {
+            conversation: self,
+            // SAFETY: `out` is a C string allocated by the FFI layer.
+            id: unsafe { take_c_string(out) }?,
+        })
+    }
+
+    /// Publish a previously prepared message by its hex-encoded ID.
+    ///
+    /// Use this when only the ID of a [`PreparedMessage`] was kept
+    /// (e.g. across restarts).
+    pub fn publish_prepared(&self, message_id: &str) -> Result<()> {
+        let c = to_c_string(message_id)?;
+        // SAFETY: Valid handle and CString.
+        error::check(unsafe {
+            xmtp_sys::xmtp_conversation_publish_stored_message(self.handle.as_ptr(), c.as_ptr())
+        })
+    }
+
     /// Publish all queued (unpublished) messages.
     pub fn publish_messages(&self) -> Result<()> {
         // SAFETY: Valid handle pointer.
@@ -334,6 +485,29 @@ pub fn list_messages(&self, options: &ListMessagesOptions) -> Result<Vec<Message
         Ok(read_enriched_message_list(list))
     }
 
+    /// Decrypt and store a group message envelope delivered by a push service.
+    ///
+    /// Intended for clients built with
+    /// [`notification_mode`](crate::ClientBuilder::notification_mode).
+    /// Returns the messages the envelope produced (empty for commits that
+    /// carry no application message), as libxmtp reports them; see
+    /// [`ProcessedMessage`] for what that leaves out.
+    pub fn process_envelope(&self, envelope: &[u8]) -> Result<Vec<ProcessedMessage>> {
+        let len = to_ffi_len(envelope.len())?;
+        let mut out: *mut xmtp_sys::XmtpFfiMessageList = ptr::null_mut();
+        // SAFETY: Valid handle and envelope buffer; `out` receives the stored messages.
+        let rc = unsafe {
+            xmtp_sys::xmtp_conversation_process_streamed_group_message(
+                self.handle.as_ptr(),
+                envelope.as_ptr(),
+                len,
+                &raw mut out,
+            )
+        };
+        error::check(rc)?;
+        read_processed_message_list(out, &self.id())
+    }
+
     /// Count messages matching filter options.
     #[must_use]
     pub fn count_messages(&self, options: &ListMessagesOptions) -> i64 {
@@ -708,6 +882,81 @@ pub(crate) fn read_enriched_message_list(
     msgs
 }
 
+/// Read the messages of a plain FFI message list, as returned for a processed
+/// envelope, into [`ProcessedMessage`] values belonging to `conversation_id`.
+fn read_processed_message_list(
+    ptr: *mut xmtp_sys::XmtpFfiMessageList,
+    conversation_id: &str,
+) -> Result<Vec<ProcessedMessage>> {
+    let list = FfiList::new(
+        ptr,
+        xmtp_sys::xmtp_message_list_len,
+        xmtp_sys::xmtp_message_list_free,
+    );
+    let mut msgs = Vec::with_capacity(ffi_usize(list.len()));
+    for i in 0..list.len() {
+        let lp = list.as_ptr();
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+        let id_ptr = unsafe { xmtp_sys::xmtp_message_id(lp, i) };
+        // SAFETY: `id_ptr` is a C string allocated by the FFI layer.
+        let id = unsafe { take_c_string(id_ptr) }?;
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+        let sender_ptr = unsafe { xmtp_sys::xmtp_message_sender_inbox_id(lp, i) };
+        // SAFETY: `sender_ptr` is a C string allocated by the FFI layer.
+        let sender_inbox_id = unsafe { take_c_string(sender_ptr) }?;
+        let mut len = 0;
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds; the bytes are borrowed.
+        let bytes = unsafe { xmtp_sys::xmtp_message_content_bytes(lp, i, &raw mut len) };
+        let content = if bytes.is_null() || len <= 0 {
+            Vec::new()
+        } else {
+            // SAFETY: `bytes` is non-null with `len` valid bytes, borrowed from `list`.
+            unsafe { std::slice::from_raw_parts(bytes, ffi_usize(len)) }.to_vec()
+        };
+        msgs.push(ProcessedMessage {
+            id,
+            conversation_id: conversation_id.to_owned(),
+            sender_inbox_id,
+            // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+            sent_at_ns: unsafe { xmtp_sys::xmtp_message_sent_at_ns(lp, i) },
+            // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+            kind: MessageKind::from_ffi(unsafe { xmtp_sys::xmtp_message_kind(lp, i) })
+                .unwrap_or(MessageKind::Application),
+            delivery_status: DeliveryStatus::from_ffi(
+                // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+                unsafe { xmtp_sys::xmtp_message_delivery_status(lp, i) },
+            )
+            .unwrap_or(DeliveryStatus::Unpublished),
+            content,
+        });
+    }
+    Ok(msgs)
+}
+
+/// Message kind and fallback text from an `EncodedContent` header.
+#[cfg(feature = "content")]
+fn preview_header(content: &[u8]) -> (Option<MessageKind>, Option<String>) {
+    use prost::Message as _;
+
+    let Ok(ec) = crate::content::EncodedContent::decode(content) else {
+        return (None, None);
+    };
+    let kind = ec.r#type.as_ref().map(|t| {
+        if crate::content::GROUP_UPDATED.same_type(t) {
+            MessageKind::MembershipChange
+        } else {
+            MessageKind::Application
+        }
+    });
+    (kind, ec.fallback)
+}
+
+/// The content header is only parsed with the `content` feature.
+#[cfg(not(feature = "content"))]
+const fn preview_header(_content: &[u8]) -> (Option<MessageKind>, Option<String>) {
+    (None, None)
+}
+
 /// Read all members from an FFI group member list.
 fn read_member_list(ptr: *mut xmtp_sys::XmtpFfiGroupMemberList) -> Result<Vec<GroupMember>> {
     let list = FfiList::new(
@@ -778,6 +1027,89 @@ pub(crate) fn read_conversation_list_inner(
     Ok(convs)
 }
 
+/// Read a conversation list including per-item last message and fork status.
+pub(crate) fn read_conversation_item_list(
+    ptr: *mut xmtp_sys::XmtpFfiConversationList,
+) -> Result<Vec<ConversationListItem>> {
+    let list = FfiList::new(
+        ptr,
+        xmtp_sys::xmtp_conversation_list_len,
+        xmtp_sys::xmtp_conversation_list_free,
+    );
+    let mut items = Vec::with_capacity(ffi_usize(list.len()));
+    for i in 0..list.len() {
+        let lp = list.as_ptr();
+        let mut conv: *mut xmtp_sys::XmtpFfiConversation = ptr::null_mut();
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+        let rc = unsafe { xmtp_sys::xmtp_conversation_list_get(lp, i, &raw mut conv) };
+        if rc != 0 || conv.is_null() {
+            continue;
+        }
+        let conversation = Conversation::from_raw(conv)?;
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+        let last_message_sent_at_ns =
+            unsafe { xmtp_sys::xmtp_conversation_list_last_message_sent_at_ns(lp, i) }.max(0);
+        let last_message = if last_message_sent_at_ns > 0 {
+            read_list_last_message(lp, i)
+        } else {
+            None
+        };
+        // SAFETY: `lp` is a valid FFI list and `i` is within bounds.
+        let commit_log_forked =
+            match unsafe { xmtp_sys::xmtp_conversation_list_is_commit_log_forked(lp, i) } {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            };
+        items.push(ConversationListItem {
+            conversation,
+            last_message,
+            last_message_sent_at_ns,
+            commit_log_forked,
+        });
+    }
+    Ok(items)
+}
+
+/// Read the last message of conversation list item `i` into a [`MessagePreview`].
+fn read_list_last_message(
+    lp: *const xmtp_sys::XmtpFfiConversationList,
+    i: i32,
+) -> Option<MessagePreview> {
+    let mut raw: *mut xmtp_sys::XmtpFfiMessage = ptr::null_mut();
+    // SAFETY: `lp` is a valid FFI list and `i` is within bounds; `raw` receives the message.
+    let rc = unsafe { xmtp_sys::xmtp_conversation_list_last_message(lp, i, &raw mut raw) };
+    if rc != 0 {
+        return None;
+    }
+    let msg = OwnedHandle::new(raw, xmtp_sys::xmtp_message_free).ok()?;
+    // SAFETY: `msg` is a valid FFI message handle.
+    let id_ptr = unsafe { xmtp_sys::xmtp_single_message_id(msg.as_ptr()) };
+    // SAFETY: `msg` is a valid FFI message handle.
+    let sender_ptr = unsafe { xmtp_sys::xmtp_single_message_sender_inbox_id(msg.as_ptr()) };
+    let mut len = 0i32;
+    // SAFETY: `msg` is a valid FFI message handle; `len` receives the byte count.
+    let bytes = unsafe { xmtp_sys::xmtp_single_message_content_bytes(msg.as_ptr(), &raw mut len) };
+    let content = if bytes.is_null() || len <= 0 {
+        Vec::new()
+    } else {
+        // SAFETY: `bytes` is non-null with `len` valid bytes borrowed from `msg`.
+        unsafe { std::slice::from_raw_parts(bytes, ffi_usize(len)) }.to_vec()
+    };
+    let (kind, fallback) = preview_header(&content);
+    Some(MessagePreview {
+        // SAFETY: `id_ptr` is a C string allocated by the FFI layer.
+        id: unsafe { take_c_string(id_ptr) }.ok()?,
+        // SAFETY: `sender_ptr` is a C string allocated by the FFI layer.
+        sender_inbox_id: unsafe { take_c_string(sender_ptr) }.unwrap_or_default(),
+        // SAFETY: `msg` is a valid FFI message handle.
+        sent_at_ns: unsafe { xmtp_sys::xmtp_single_message_sent_at_ns(msg.as_ptr()) },
+        content,
+        fallback,
+        kind,
+    })
+}
+
 /// Read permissions from an FFI struct.
 fn read_permissions(p: &xmtp_sys::XmtpFfiGroupPermissions) -> Permissions {
     let ps = &p.policy_set;
@@ -16,6 +16,10 @@
     reason = "FFI boundary casts are systematic and bounds-checked; every public function follows the same error pattern"
 )]
 
+// The tokio dev-dependency only drives the `async` tests.
+#[cfg(all(test, not(feature = "async")))]
+use tokio as _;
+
 pub mod client;
 pub mod conversation;
 pub mod error;
@@ -32,35 +36,55 @@
 #[cfg(feature = "alloy")]
 mod signer;
 
+#[cfg(feature = "async")]
+mod async_client;
+
 #[cfg(feature = "ledger")]
 mod ledger;
 
 #[cfg(feature = "ens")]
 mod ens;
 
+#[cfg(feature = "passkey")]
+mod passkey;
+
 // Re-export core public API at crate root.
-pub use client::{Client, ClientBuilder};
+#[cfg(feature = "async")]
+pub use async_client::{AsyncClient, AsyncConversation};
+pub use client::{AuthHandle, Client, ClientBuilder};
 // Re-export standalone functions.
 pub use client::{
-    generate_inbox_id, get_inbox_id_for_identifier, init_logger, is_address_authorized,
-    is_installation_authorized, libxmtp_version, verify_signed_with_public_key,
+    archive_metadata, generate_inbox_id, get_inbox_id_for_identifier, init_logger,
+    is_address_authorized, is_installation_authorized, libxmtp_version,
+    verify_signed_with_public_key,
+};
+pub use conversation::{
+    Conversation, ConversationListItem, GroupMember, Message, MessagePreview, PreparedMessage,
+    ProcessedMessage,
 };
-pub use conversation::{Conversation, GroupMember, Message};
 #[cfg(feature = "ens")]
 pub use ens::{DEFAULT_RPC, EnsResolver};
 pub use error::{Result, XmtpError};
 #[cfg(feature = "ledger")]
 pub use ledger::LedgerSigner;
+#[cfg(feature = "passkey")]
+pub use passkey::PasskeySigner;
 pub use resolve::{Recipient, Resolver};
 #[cfg(feature = "alloy")]
 pub use signer::AlloySigner;
-pub use stream::{ConsentUpdate, MessageEvent, PreferenceUpdate, Subscription};
+#[cfg(feature = "content")]
+pub use stream::DecodedMessage;
+pub use stream::{
+    ClientEvent, CloseReason, ConsentUpdate, EventKind, EventSelection, MessageEvent, Overflow,
+    PreferenceUpdate, ResilientSubscription, StreamEvent, StreamFilter, Subscription,
+};
 pub use types::{
-    AccountIdentifier, ApiStats, ConsentEntityType, ConsentState, ConversationDebugInfo,
-    ConversationMetadata, ConversationOrderBy, ConversationType, CreateDmOptions,
-    CreateGroupOptions, Cursor, DeliveryStatus, DisappearingSettings, Env, GroupPermissionsPreset,
-    HmacKey, HmacKeyEntry, IdentifierKind, IdentityStats, InboxState, KeyPackageStatus,
-    LastReadTime, ListConversationsOptions, ListMessagesOptions, MembershipState, MessageKind,
-    MetadataField, PermissionLevel, PermissionPolicy, PermissionPolicySet, PermissionUpdateType,
+    AccountIdentifier, ApiStats, ArchiveElement, ArchiveMetadata, ArchiveOptions, AvailableArchive,
+    ConsentEntityType, ConsentState, ConversationDebugInfo, ConversationMetadata,
+    ConversationOrderBy, ConversationType, CreateDmOptions, CreateGroupOptions, Cursor,
+    DeliveryStatus, DisappearingSettings, Env, GroupPermissionsPreset, HmacKey, HmacKeyEntry,
+    IdentifierKind, IdentityStats, InboxState, KeyPackageStatus, LastReadTime,
+    ListConversationsOptions, ListMessagesOptions, MembershipState, MessageKind, MetadataField,
+    PasskeySignature, PermissionLevel, PermissionPolicy, PermissionPolicySet, PermissionUpdateType,
     Permissions, PreferenceKind, SendOptions, Signer, SortDirection, SyncResult,
 };
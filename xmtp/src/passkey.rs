@@ -0,0 +1,165 @@
+//! Software passkey (WebAuthn) signer backed by [`p256`](https://docs.rs/p256).
+//!
+//! Enabled via the `passkey` Cargo feature:
+//!
+//! ```toml
+//! [dependencies]
+//! xmtp = { version = "0.1", features = ["passkey"] }
+//! ```
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use p256::ecdsa::signature::Signer as _;
+use p256::ecdsa::{DerSignature, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, XmtpError};
+use crate::types::{AccountIdentifier, IdentifierKind, PasskeySignature, Signer};
+
+/// Authenticator data flags: user present (UP) and user verified (UV).
+const FLAGS_UP_UV: u8 = 0x05;
+
+/// A software passkey that produces WebAuthn assertions with a local P-256 key.
+///
+/// Behaves like a platform authenticator for relying party `rp_id`, which
+/// makes it possible to register and manage passkey-backed inboxes without a
+/// browser or hardware key. Intended for tests and tooling; a real passkey
+/// never exposes its private key.
+pub struct PasskeySigner {
+    key: SigningKey,
+    rp_id: String,
+    sign_count: AtomicU32,
+}
+
+impl std::fmt::Debug for PasskeySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasskeySigner")
+            .field("public_key", &hex::encode(self.public_key()))
+            .field("rp_id", &self.rp_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PasskeySigner {
+    /// Create a signer from raw 32-byte P-256 private key material.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`XmtpError::Signing`] if the bytes are not a valid P-256
+    /// secret key.
+    pub fn from_bytes(key: &[u8; 32], rp_id: &str) -> Result<Self> {
+        let key = SigningKey::from_slice(key).map_err(|e| XmtpError::Signing(e.to_string()))?;
+        Ok(Self {
+            key,
+            rp_id: rp_id.to_owned(),
+            sign_count: AtomicU32::new(0),
+        })
+    }
+
+    /// Generate a random passkey for relying party `rp_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`XmtpError::Signing`] if the system RNG is unavailable.
+    pub fn random(rp_id: &str) -> Result<Self> {
+        loop {
+            let mut bytes = [0u8; 32];
+            getrandom::fill(&mut bytes).map_err(|e| XmtpError::Signing(format!("rng: {e}")))?;
+            // Out-of-range scalars are astronomically rare; just draw again.
+            if let Ok(signer) = Self::from_bytes(&bytes, rp_id) {
+                return Ok(signer);
+            }
+        }
+    }
+
+    /// Uncompressed SEC1 public key (65 bytes).
+    #[must_use]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Relying party ID this passkey is scoped to.
+    #[must_use]
+    pub fn rp_id(&self) -> &str {
+        &self.rp_id
+    }
+
+    /// Build `authenticatorData`: `sha256(rp_id) || flags || sign_count`.
+    fn authenticator_data(&self) -> Vec<u8> {
+        let count = self.sign_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        data.push(FLAGS_UP_UV);
+        data.extend_from_slice(&count.to_be_bytes());
+        data
+    }
+}
+
+impl Signer for PasskeySigner {
+    fn identifier(&self) -> AccountIdentifier {
+        AccountIdentifier {
+            address: hex::encode(self.public_key()),
+            kind: IdentifierKind::Passkey,
+        }
+    }
+
+    fn sign(&self, text: &str) -> Result<Vec<u8>> {
+        Ok(self.sign_passkey(text)?.signature)
+    }
+
+    fn sign_passkey(&self, text: &str) -> Result<PasskeySignature> {
+        let client_data_json = serde_json::to_vec(&serde_json::json!({
+            "type": "webauthn.get",
+            "challenge": URL_SAFE_NO_PAD.encode(text.as_bytes()),
+            "origin": format!("https://{}", self.rp_id),
+            "crossOrigin": false,
+        }))
+        .map_err(|e| XmtpError::Signing(e.to_string()))?;
+        let authenticator_data = self.authenticator_data();
+        let mut signed = authenticator_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&client_data_json));
+        let signature: DerSignature = self.key.sign(&signed);
+        Ok(PasskeySignature {
+            public_key: self.public_key(),
+            signature: signature.as_bytes().to_vec(),
+            authenticator_data,
+            client_data_json,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::ecdsa::signature::Verifier as _;
+    use p256::ecdsa::{Signature, VerifyingKey};
+
+    use super::*;
+
+    #[test]
+    fn assertion_verifies_and_encodes_challenge() {
+        let signer = PasskeySigner::from_bytes(&[7u8; 32], "example.com").unwrap();
+        let sig = signer.sign_passkey("hello xmtp").unwrap();
+
+        let mut signed = sig.authenticator_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&sig.client_data_json));
+        let vk = VerifyingKey::from_sec1_bytes(&sig.public_key).unwrap();
+        let der = Signature::from_der(&sig.signature).unwrap();
+        vk.verify(&signed, &der).unwrap();
+
+        let client: serde_json::Value = serde_json::from_slice(&sig.client_data_json).unwrap();
+        let challenge = URL_SAFE_NO_PAD
+            .decode(client.get("challenge").and_then(|c| c.as_str()).unwrap())
+            .unwrap();
+        assert_eq!(challenge, b"hello xmtp");
+        assert_eq!(
+            sig.authenticator_data.get(32..),
+            Some(&[FLAGS_UP_UV, 0, 0, 0, 1][..])
+        );
+        assert_eq!(signer.identifier().kind, IdentifierKind::Passkey);
+    }
+}
@@ -0,0 +1,426 @@
+//! Single-consumer channel shared by all subscriptions.
+//!
+//! Unlike `std::sync::mpsc`, the receiver can be polled from async code (the
+//! sender wakes the last registered [`Waker`]) and either side can close the
+//! channel explicitly, recording a [`CloseReason`]. Items queued before
+//! closing are still delivered. Per-item errors are queued in order with the
+//! items and do not close the channel. The channel is unbounded until
+//! [`Receiver::set_capacity`] is called; a full channel then applies its
+//! [`Overflow`] policy.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::Waker;
+#[cfg(any(feature = "async", test))]
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use super::{CloseReason, Overflow, StreamEvent};
+
+struct State<T> {
+    /// Items, and errors reported for single items.
+    queue: VecDeque<Result<T, String>>,
+    closed: bool,
+    reason: Option<CloseReason>,
+    last_error: Option<String>,
+    waker: Option<Waker>,
+    capacity: Option<usize>,
+    overflow: Overflow,
+    dropped: u64,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+    space: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Mark closed and wake any waiting receiver. The first reason wins.
+    fn close(&self, reason: CloseReason) {
+        let waker = {
+            let mut state = self.lock();
+            state.closed = true;
+            state.reason.get_or_insert(reason);
+            state.waker.take()
+        };
+        self.ready.notify_all();
+        self.space.notify_all();
+        if let Some(w) = waker {
+            w.wake();
+        }
+    }
+
+    /// Wait for or make room for one more value according to the overflow
+    /// policy. Returns `None` if the value must be discarded.
+    fn make_room<'a>(
+        &self,
+        mut state: MutexGuard<'a, State<T>>,
+    ) -> Option<MutexGuard<'a, State<T>>> {
+        loop {
+            if state.closed {
+                return None;
+            }
+            if state.capacity.is_none_or(|cap| state.queue.len() < cap) {
+                return Some(state);
+            }
+            match state.overflow {
+                Overflow::Block => {
+                    state = self
+                        .space
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+                Overflow::DropOldest => {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                    return Some(state);
+                }
+                Overflow::DropNewest => {
+                    state.dropped += 1;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Queue an item or error, applying the overflow policy.
+    ///
+    /// Dropped silently if the channel is closed.
+    fn push(&self, value: Result<T, String>) {
+        let waker = {
+            let Some(mut state) = self.make_room(self.lock()) else {
+                return;
+            };
+            if let Err(e) = &value {
+                state.last_error = Some(e.clone());
+            }
+            state.queue.push_back(value);
+            state.waker.take()
+        };
+        self.ready.notify_one();
+        if let Some(w) = waker {
+            w.wake();
+        }
+    }
+
+    /// Pop the next entry, letting a sender blocked on a full queue proceed.
+    fn pop(&self, state: &mut State<T>) -> Option<Result<T, String>> {
+        let v = state.queue.pop_front()?;
+        self.space.notify_one();
+        Some(v)
+    }
+
+    /// Pop the next item, discarding errors queued before it.
+    fn pop_item(&self, state: &mut State<T>) -> Option<T> {
+        while let Some(v) = self.pop(state) {
+            if let Ok(item) = v {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// Producing half, owned by the FFI callback context.
+pub(crate) struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Reports errors and the end of the stream from the FFI on-close callback.
+pub(crate) struct Closer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Closer<T> {
+    /// Close the channel, recording why.
+    pub(crate) fn close(&self, reason: CloseReason) {
+        self.shared.close(reason);
+    }
+
+    /// Queue an error for a single item without closing the channel.
+    pub(crate) fn error(&self, err: String) {
+        self.shared.push(Err(err));
+    }
+}
+
+/// Consuming half, owned by the subscription.
+pub(crate) struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Create a connected sender/receiver pair.
+pub(crate) fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            closed: false,
+            reason: None,
+            last_error: None,
+            waker: None,
+            capacity: None,
+            overflow: Overflow::default(),
+            dropped: 0,
+        }),
+        ready: Condvar::new(),
+        space: Condvar::new(),
+    });
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+impl<T> Sender<T> {
+    /// Queue a value, applying the overflow policy if the channel is full.
+    ///
+    /// Dropped silently if the channel is closed.
+    pub(crate) fn send(&self, value: T) {
+        self.shared.push(Ok(value));
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.close(CloseReason::Ended);
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Wait up to `timeout` for the next item or error. Returns
+    /// [`StreamEvent::Closed`] once closed and drained, or `None` on timeout.
+    pub(crate) fn recv_event_timeout(&self, timeout: Duration) -> Option<StreamEvent<T>> {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(v) = self.shared.pop(&mut state) {
+                return Some(v.map_or_else(StreamEvent::Error, StreamEvent::Item));
+            }
+            if state.closed {
+                let reason = state.reason.clone().unwrap_or(CloseReason::Ended);
+                return Some(StreamEvent::Closed(reason));
+            }
+            let wait = match deadline {
+                Some(d) => d
+                    .checked_duration_since(Instant::now())
+                    .filter(|w| !w.is_zero())?,
+                None => Duration::MAX,
+            };
+            state = self
+                .shared
+                .ready
+                .wait_timeout(state, wait)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Take a queued item without blocking, discarding errors before it.
+    pub(crate) fn try_recv(&self) -> Option<T> {
+        self.shared.pop_item(&mut self.shared.lock())
+    }
+
+    /// Poll for the next item, registering `cx`'s waker if none is queued.
+    /// Errors are discarded.
+    #[cfg(any(feature = "async", test))]
+    pub(crate) fn poll_recv(&self, cx: &Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.shared.lock();
+        if let Some(v) = self.shared.pop_item(&mut state) {
+            return Poll::Ready(Some(v));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        match &mut state.waker {
+            Some(w) => w.clone_from(cx.waker()),
+            slot @ None => *slot = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+
+    /// Close the channel from the receiving side.
+    pub(crate) fn close(&self) {
+        self.shared.close(CloseReason::Ended);
+    }
+
+    /// Close the channel because the producer stopped without saying why.
+    /// The last reported error, if any, becomes the reason.
+    pub(crate) fn finish(&self) {
+        let reason = self
+            .shared
+            .lock()
+            .last_error
+            .clone()
+            .map_or(CloseReason::Ended, CloseReason::Error);
+        self.shared.close(reason);
+    }
+
+    /// Why the channel was closed, if it has been.
+    pub(crate) fn close_reason(&self) -> Option<CloseReason> {
+        self.shared.lock().reason.clone()
+    }
+
+    /// Bound the queue to `capacity` items (at least one), applying `overflow`
+    /// when a value arrives while it is full.
+    pub(crate) fn set_capacity(&self, capacity: usize, overflow: Overflow) {
+        {
+            let mut state = self.shared.lock();
+            state.capacity = Some(capacity.max(1));
+            state.overflow = overflow;
+        }
+        // A blocked sender re-checks against the new limit.
+        self.shared.space.notify_all();
+    }
+
+    /// Number of values discarded by the overflow policy so far.
+    pub(crate) fn dropped(&self) -> u64 {
+        self.shared.lock().dropped
+    }
+
+    /// A handle that can close this channel with a reason.
+    pub(crate) fn closer(&self) -> Closer<T> {
+        Closer {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::Wake;
+
+    use super::*;
+
+    struct Flag(AtomicBool);
+
+    /// Block for the next item, like `Subscription::recv`.
+    fn recv<T>(rx: &Receiver<T>) -> Option<T> {
+        loop {
+            match rx.recv_event_timeout(Duration::MAX)? {
+                StreamEvent::Item(v) => return Some(v),
+                StreamEvent::Error(_) => {}
+                StreamEvent::Closed(_) => return None,
+            }
+        }
+    }
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn send_wakes_pending_poll_and_close_ends_after_drain() {
+        let (tx, rx) = channel();
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let cx = Context::from_waker(&waker);
+
+        assert_eq!(rx.poll_recv(&cx), Poll::Pending);
+        tx.send(1);
+        assert!(flag.0.load(Ordering::SeqCst));
+        tx.send(2);
+        drop(tx);
+
+        assert_eq!(rx.poll_recv(&cx), Poll::Ready(Some(1)));
+        assert_eq!(recv(&rx), Some(2));
+        assert_eq!(rx.poll_recv(&cx), Poll::Ready(None));
+        assert_eq!(recv(&rx), None);
+        assert_eq!(rx.close_reason(), Some(CloseReason::Ended));
+    }
+
+    #[test]
+    fn first_close_reason_wins() {
+        let (tx, rx) = channel::<u8>();
+        rx.closer().close(CloseReason::Error("network".into()));
+        drop(tx);
+        rx.close();
+        assert_eq!(
+            rx.close_reason(),
+            Some(CloseReason::Error("network".into()))
+        );
+    }
+
+    #[test]
+    fn errors_stay_in_order_and_finish_reports_the_last() {
+        let (tx, rx) = channel();
+        let closer = rx.closer();
+        tx.send(1);
+        closer.error("bad item".into());
+        tx.send(2);
+
+        assert_eq!(
+            rx.recv_event_timeout(Duration::ZERO),
+            Some(StreamEvent::Item(1))
+        );
+        assert_eq!(
+            rx.recv_event_timeout(Duration::ZERO),
+            Some(StreamEvent::Error("bad item".into()))
+        );
+        assert_eq!(rx.try_recv(), Some(2));
+        assert_eq!(rx.recv_event_timeout(Duration::ZERO), None);
+
+        rx.finish();
+        assert_eq!(
+            rx.recv_event_timeout(Duration::ZERO),
+            Some(StreamEvent::Closed(CloseReason::Error("bad item".into())))
+        );
+    }
+
+    #[test]
+    fn drop_oldest_keeps_newest_and_counts() {
+        let (tx, rx) = channel();
+        rx.set_capacity(2, Overflow::DropOldest);
+        for i in 0..5 {
+            tx.send(i);
+        }
+        assert_eq!(rx.try_recv(), Some(3));
+        assert_eq!(rx.try_recv(), Some(4));
+        assert_eq!(rx.dropped(), 3);
+    }
+
+    #[test]
+    fn drop_newest_keeps_oldest_and_counts() {
+        let (tx, rx) = channel();
+        rx.set_capacity(2, Overflow::DropNewest);
+        for i in 0..5 {
+            tx.send(i);
+        }
+        assert_eq!(rx.try_recv(), Some(0));
+        assert_eq!(rx.try_recv(), Some(1));
+        assert_eq!(rx.try_recv(), None);
+        assert_eq!(rx.dropped(), 3);
+    }
+
+    #[test]
+    fn block_waits_for_space_and_close_releases_sender() {
+        let (tx, rx) = channel();
+        rx.set_capacity(1, Overflow::Block);
+        let t = std::thread::spawn(move || {
+            tx.send(1);
+            tx.send(2);
+            tx.send(3);
+        });
+        assert_eq!(recv(&rx), Some(1));
+        assert_eq!(recv(&rx), Some(2));
+        rx.close();
+        t.join().unwrap();
+        assert_eq!(rx.dropped(), 0);
+    }
+
+    #[test]
+    fn blocking_recv_returns_value_from_other_thread() {
+        let (tx, rx) = channel();
+        let t = std::thread::spawn(move || tx.send("hi"));
+        assert_eq!(recv(&rx), Some("hi"));
+        t.join().unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! One subscription merging every client-level stream.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use super::{ConsentUpdate, PreferenceUpdate, StreamFilter, channel};
+use crate::conversation::{Conversation, Message};
+use crate::types::{ConsentState, ConversationType};
+
+/// An event from [`Client::events`](crate::Client::events).
+#[derive(Debug)]
+pub enum ClientEvent {
+    /// A new message (see [`enriched_messages`](super::enriched_messages)).
+    Message(Box<Message>),
+    /// A new conversation.
+    Conversation(Conversation),
+    /// Consent state changes.
+    Consent(Vec<ConsentUpdate>),
+    /// Preference updates.
+    Preferences(Vec<PreferenceUpdate>),
+    /// A message was deleted. Carries the hex message ID.
+    MessageDeleted(String),
+}
+
+impl ClientEvent {
+    /// The [`EventKind`] this event belongs to.
+    #[must_use]
+    pub const fn kind(&self) -> EventKind {
+        match self {
+            Self::Message(_) => EventKind::Message,
+            Self::Conversation(_) => EventKind::Conversation,
+            Self::Consent(_) => EventKind::Consent,
+            Self::Preferences(_) => EventKind::Preference,
+            Self::MessageDeleted(_) => EventKind::Deletion,
+        }
+    }
+}
+
+/// An event kind that [`Client::events`](crate::Client::events) can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventKind {
+    /// New messages.
+    Message,
+    /// New conversations.
+    Conversation,
+    /// Consent state changes.
+    Consent,
+    /// Preference updates.
+    Preference,
+    /// Message deletions.
+    Deletion,
+}
+
+/// Which streams [`Client::events`](crate::Client::events) merges, and how messages are narrowed.
+///
+/// ```no_run
+/// # fn run(client: &xmtp::Client) -> xmtp::Result<()> {
+/// use xmtp::stream::{ClientEvent, EventKind, EventSelection};
+///
+/// let selection = EventSelection::new()
+///     .with(EventKind::Message)
+///     .with(EventKind::Conversation);
+/// for event in client.events(&selection)? {
+///     if let ClientEvent::Message(msg) = event {
+///         println!("{}", msg.id);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventSelection {
+    pub(super) kinds: BTreeSet<EventKind>,
+    pub(super) conversation_type: Option<ConversationType>,
+    pub(super) consent_states: Vec<ConsentState>,
+    pub(super) message_filter: StreamFilter,
+}
+
+impl EventSelection {
+    /// An empty selection; add kinds with [`with`](Self::with).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select every event kind, unfiltered.
+    #[must_use]
+    pub fn all() -> Self {
+        [
+            EventKind::Message,
+            EventKind::Conversation,
+            EventKind::Consent,
+            EventKind::Preference,
+            EventKind::Deletion,
+        ]
+        .into_iter()
+        .fold(Self::new(), Self::with)
+    }
+
+    /// Add an event kind.
+    #[must_use]
+    pub fn with(mut self, kind: EventKind) -> Self {
+        self.kinds.insert(kind);
+        self
+    }
+
+    /// Restrict messages and conversations to one type (default: all).
+    #[must_use]
+    pub const fn conversation_type(mut self, conversation_type: ConversationType) -> Self {
+        self.conversation_type = Some(conversation_type);
+        self
+    }
+
+    /// Restrict messages to conversations in these consent states (default: all).
+    #[must_use]
+    pub fn consent_states(mut self, states: &[ConsentState]) -> Self {
+        self.consent_states = states.to_vec();
+        self
+    }
+
+    /// Filter messages before delivery.
+    #[must_use]
+    pub fn message_filter(mut self, filter: StreamFilter) -> Self {
+        self.message_filter = filter;
+        self
+    }
+
+    pub(super) fn has(&self, kind: EventKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+}
+
+/// A stream callback that wraps each item with `wrap` and queues it on `tx`.
+pub(super) fn tag<T: 'static>(
+    tx: &Arc<channel::Sender<ClientEvent>>,
+    wrap: fn(T) -> ClientEvent,
+) -> Box<dyn Fn(T) + Send> {
+    let tx = Arc::clone(tx);
+    Box::new(move |item| tx.send(wrap(item)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PreferenceKind;
+
+    #[test]
+    fn selection_maps_to_stream_kinds() {
+        let all = EventSelection::all();
+        assert_eq!(all.kinds.len(), 5);
+
+        let some = EventSelection::new()
+            .with(EventKind::Consent)
+            .with(EventKind::Deletion)
+            .with(EventKind::Consent);
+        assert_eq!(
+            some.kinds.iter().copied().collect::<Vec<_>>(),
+            [EventKind::Consent, EventKind::Deletion]
+        );
+        assert!(!some.has(EventKind::Message));
+        assert!(EventSelection::new().kinds.is_empty());
+    }
+
+    #[test]
+    fn callbacks_tag_events_with_their_kind() {
+        let (tx, rx) = channel::channel();
+        let tx = Arc::new(tx);
+        tag(&tx, ClientEvent::Consent)(Vec::new());
+        tag(&tx, ClientEvent::Preferences)(vec![PreferenceUpdate {
+            kind: PreferenceKind::Consent,
+            consent: None,
+        }]);
+        tag(&tx, ClientEvent::MessageDeleted)("ab".to_owned());
+
+        let kinds: Vec<_> = std::iter::from_fn(|| rx.try_recv())
+            .map(|e| e.kind())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Consent,
+                EventKind::Preference,
+                EventKind::Deletion
+            ]
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Message filters applied inside the stream callback.
+
+use std::collections::BTreeSet;
+
+#[cfg(feature = "content")]
+use crate::content::ContentTypeId;
+use crate::conversation::Message;
+use crate::types::MessageKind;
+
+/// Selects which messages a filtered stream delivers.
+///
+/// Every configured criterion must match; an empty set means "any". Build one
+/// with chained calls and pass it to [`filtered_messages`](super::filtered_messages):
+///
+/// ```no_run
+/// # fn run(client: &xmtp::Client) -> xmtp::Result<()> {
+/// use xmtp::stream::{self, StreamFilter};
+///
+/// let filter = StreamFilter::new()
+///     .content_types(["xmtp.org/text", "xmtp.org/reply"])
+///     .exclude_own_messages()
+///     .allow_senders(["inbox-a", "inbox-b"]);
+/// for msg in stream::filtered_messages(client, None, &[], &filter)? {
+///     println!("{}: {:?}", msg.sender_inbox_id, msg.fallback);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamFilter {
+    #[cfg(feature = "content")]
+    content_types: Vec<TypeFilter>,
+    exclude_own: bool,
+    allow_senders: BTreeSet<String>,
+    deny_senders: BTreeSet<String>,
+    conversations: BTreeSet<String>,
+    kind: Option<MessageKind>,
+}
+
+impl StreamFilter {
+    /// A filter that matches every message.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only deliver these content types.
+    ///
+    /// An ID with a version (`"xmtp.org/reply:1.0"`) matches any compatible
+    /// version, i.e. the same major version (`1.1` but not `2.0`); one
+    /// without (`"xmtp.org/reply"`) matches any version. An entry that is not
+    /// a valid content type ID matches nothing.
+    #[cfg(feature = "content")]
+    #[must_use]
+    pub fn content_types<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.content_types
+            .extend(ids.into_iter().map(|id| TypeFilter::parse(id.as_ref())));
+        self
+    }
+
+    /// Skip messages sent by this client's inbox.
+    #[must_use]
+    pub const fn exclude_own_messages(mut self) -> Self {
+        self.exclude_own = true;
+        self
+    }
+
+    /// Only deliver messages from these sender inbox IDs.
+    #[must_use]
+    pub fn allow_senders<I, S>(mut self, inbox_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allow_senders
+            .extend(inbox_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Never deliver messages from these sender inbox IDs.
+    #[must_use]
+    pub fn deny_senders<I, S>(mut self, inbox_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.deny_senders
+            .extend(inbox_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only deliver messages from these conversation IDs (hex).
+    #[must_use]
+    pub fn conversations<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.conversations.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only deliver messages of this kind.
+    #[must_use]
+    pub const fn kind(mut self, kind: MessageKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Whether matching needs the client's own inbox ID.
+    pub(crate) const fn excludes_own(&self) -> bool {
+        self.exclude_own
+    }
+
+    /// Cheap pre-check on the conversation ID, before the message is loaded.
+    pub(crate) fn matches_conversation(&self, conversation_id: &str) -> bool {
+        self.conversations.is_empty() || self.conversations.contains(conversation_id)
+    }
+
+    /// Whether `msg` passes every criterion. `own_inbox_id` is required only
+    /// when own messages are excluded.
+    pub(crate) fn matches(&self, msg: &Message, own_inbox_id: Option<&str>) -> bool {
+        let sender = msg.sender_inbox_id.as_str();
+        self.matches_conversation(&msg.conversation_id)
+            && self.kind.is_none_or(|k| k == msg.kind)
+            && !(self.exclude_own && own_inbox_id == Some(sender))
+            && (self.allow_senders.is_empty() || self.allow_senders.contains(sender))
+            && !self.deny_senders.contains(sender)
+            && self.matches_type(msg)
+    }
+
+    #[cfg(feature = "content")]
+    fn matches_type(&self, msg: &Message) -> bool {
+        self.content_types.is_empty()
+            || msg
+                .content_type_id()
+                .is_some_and(|id| self.content_types.iter().any(|f| f.matches(&id)))
+    }
+
+    #[cfg(not(feature = "content"))]
+    #[allow(
+        clippy::unused_self,
+        reason = "content types are only filtered with `content`"
+    )]
+    const fn matches_type(&self, _msg: &Message) -> bool {
+        true
+    }
+}
+
+/// A parsed [`StreamFilter::content_types`] entry.
+#[cfg(feature = "content")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeFilter {
+    /// `authority/type`: any version.
+    AnyVersion(ContentTypeId),
+    /// `authority/type:major[.minor]`: the same major version.
+    Compatible(ContentTypeId),
+    /// Not a valid content type ID.
+    Invalid,
+}
+
+#[cfg(feature = "content")]
+impl TypeFilter {
+    fn parse(s: &str) -> Self {
+        let parsed = if s.contains(':') {
+            s.parse().map(Self::Compatible)
+        } else {
+            format!("{s}:0").parse().map(Self::AnyVersion)
+        };
+        parsed.unwrap_or(Self::Invalid)
+    }
+
+    fn matches(&self, id: &ContentTypeId) -> bool {
+        match self {
+            Self::AnyVersion(want) => want.same_type(id),
+            Self::Compatible(want) => want.is_compatible(id),
+            Self::Invalid => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "content")]
+mod tests {
+    use super::*;
+
+    fn message(sender: &str, content_type: &str) -> Message {
+        Message {
+            content_type: Some(content_type.into()),
+            ..Message::fixture("m", sender, Vec::new())
+        }
+    }
+
+    #[test]
+    fn all_criteria_must_match() {
+        let filter = StreamFilter::new()
+            .content_types(["xmtp.org/text", "xmtp.org/reply:1.0", "not-a-type"])
+            .exclude_own_messages()
+            .deny_senders(["spammer"])
+            .conversations(["c1"])
+            .kind(MessageKind::Application);
+        let me = Some("me");
+
+        assert!(filter.matches(&message("alice", "xmtp.org/text:1.0"), me));
+        assert!(filter.matches(&message("alice", "xmtp.org/reply:1.0"), me));
+        assert!(filter.matches(&message("alice", "xmtp.org/reply:1.1"), me));
+        assert!(filter.matches(&message("alice", "xmtp.org/text:3.0"), me));
+        assert!(!filter.matches(&message("alice", "xmtp.org/reply:2.0"), me));
+        assert!(!filter.matches(&message("alice", "xmtp.org/reaction:2.0"), me));
+        assert!(!filter.matches(&message("alice", "not-a-type"), me));
+        assert!(!filter.matches(&message("me", "xmtp.org/text:1.0"), me));
+        assert!(!filter.matches(&message("spammer", "xmtp.org/text:1.0"), me));
+        assert!(!filter.matches_conversation("c2"));
+
+        let allow = StreamFilter::new().allow_senders(["bob"]);
+        assert!(allow.matches(&message("bob", "x"), None));
+        assert!(!allow.matches(&message("alice", "x"), None));
+    }
+}
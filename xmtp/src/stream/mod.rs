@@ -0,0 +1,987 @@
+#![allow(
+    unsafe_code,
+    reason = "Streaming callbacks require unsafe for FFI trampoline functions and raw pointer casts"
+)]
+//! Channel-based streaming for real-time event subscriptions.
+//!
+//! Each function returns a [`Subscription<T>`] that yields typed events via
+//! an internal channel. Implements [`Iterator`] for idiomatic consumption and,
+//! with the `async` feature, `futures_core::Stream`.
+//! The stream stops when the subscription is dropped; why it stopped is
+//! reported by [`Subscription::close_reason`]. [`ResilientSubscription`]
+//! reconnects message streams automatically.
+
+mod channel;
+mod events;
+mod filter;
+mod resilient;
+
+use std::ffi::{CStr, c_char, c_void};
+use std::mem::ManuallyDrop;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use std::{fmt, ptr};
+
+pub use events::{ClientEvent, EventKind, EventSelection};
+pub use filter::StreamFilter;
+pub use resilient::{Backoff, ResilientSubscription, resilient_messages};
+
+use crate::client::Client;
+#[cfg(feature = "content")]
+use crate::content::{CodecRegistry, Content};
+use crate::conversation::{Conversation, Message};
+use crate::error::{self, Result, XmtpError};
+use crate::ffi::{OwnedHandle, to_ffi_len};
+use crate::types::{ConsentEntityType, ConsentState, ConversationType, PreferenceKind};
+
+/// FFI on-close callback: receives a borrowed error string (null = normal close).
+type OnCloseFn = Option<unsafe extern "C" fn(*const c_char, *mut c_void)>;
+
+/// How often a blocked receive checks whether the FFI stream has finished.
+const END_POLL: Duration = Duration::from_millis(250);
+
+/// How long dropping a stream waits for libxmtp to stop calling back.
+const END_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why a stream stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Closed normally (by [`Subscription::close`], drop, or the server).
+    Ended,
+    /// Closed by an error, e.g. a dropped network connection.
+    Error(String),
+}
+
+/// What a bounded [`Subscription`] does when an event arrives while it is full.
+///
+/// The default is [`DropOldest`](Self::DropOldest), which never blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Block the stream callback until the consumer makes room.
+    ///
+    /// **Use with care.** The callback runs on one of libxmtp's tokio worker
+    /// threads, so a slow consumer stalls that worker. With a small worker
+    /// pool this delays every other stream and FFI call, and can deadlock if
+    /// the consumer itself waits on one of them. Only use it when the
+    /// consumer drains the subscription promptly on a thread of its own.
+    Block,
+    /// Discard the oldest queued event to make room.
+    #[default]
+    DropOldest,
+    /// Discard the incoming event.
+    DropNewest,
+}
+
+/// An item, a per-item error, or the terminal close event of a
+/// [`Subscription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent<T> {
+    /// A stream item.
+    Item(T),
+    /// libxmtp failed to deliver one item. The stream keeps running.
+    Error(String),
+    /// The stream stopped; no further items follow.
+    Closed(CloseReason),
+}
+
+/// A real-time event subscription backed by an internal channel.
+///
+/// Yields events of type `T` via [`recv`](Self::recv),
+/// [`try_recv`](Self::try_recv), or [`Iterator`] consumption. With the
+/// `async` feature it is also a `futures_core::Stream` that is woken
+/// by the FFI callback, so several subscriptions can be `select!`ed in one task.
+/// The underlying FFI stream is stopped when this value is dropped.
+///
+/// Events are queued without limit unless the subscription is made
+/// [`bounded`](Self::bounded).
+pub struct Subscription<T> {
+    rx: channel::Receiver<T>,
+    streams: Vec<FfiStream>,
+}
+
+impl<T> Subscription<T> {
+    /// Combine a channel receiver with the FFI streams feeding it.
+    const fn new(rx: channel::Receiver<T>, streams: Vec<FfiStream>) -> Self {
+        Self { rx, streams }
+    }
+
+    /// Block until the next event, or `None` if the stream ended.
+    ///
+    /// Per-item errors are skipped; use [`recv_event`](Self::recv_event) to
+    /// see them.
+    #[must_use]
+    pub fn recv(&self) -> Option<T> {
+        loop {
+            match self.recv_event() {
+                StreamEvent::Item(v) => return Some(v),
+                StreamEvent::Error(_) => {}
+                StreamEvent::Closed(_) => return None,
+            }
+        }
+    }
+
+    /// Non-blocking receive. Returns `None` if no event is ready.
+    #[must_use]
+    pub fn try_recv(&self) -> Option<T> {
+        self.rx.try_recv()
+    }
+
+    /// Signal the stream to stop. Safe to call multiple times.
+    ///
+    /// Events already queued are still delivered; after that the
+    /// subscription yields `None`.
+    pub fn close(&self) {
+        for stream in &self.streams {
+            stream.end();
+        }
+        self.rx.close();
+    }
+
+    /// Limit the queue to `capacity` events (at least one), handling a full
+    /// queue according to `overflow`.
+    ///
+    /// ```no_run
+    /// # fn run(client: &xmtp::Client) -> xmtp::Result<()> {
+    /// use xmtp::stream::{self, Overflow};
+    ///
+    /// let sub = stream::messages(client, None, &[])?.bounded(1024, Overflow::DropOldest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn bounded(self, capacity: usize, overflow: Overflow) -> Self {
+        self.rx.set_capacity(capacity, overflow);
+        self
+    }
+
+    /// Number of events discarded by the [`Overflow`] policy so far.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.rx.dropped()
+    }
+
+    /// Block until the next item, per-item error, or the close event.
+    #[must_use]
+    pub fn recv_event(&self) -> StreamEvent<T> {
+        loop {
+            if let Some(event) = self.rx.recv_event_timeout(END_POLL) {
+                return event;
+            }
+            self.detect_end();
+        }
+    }
+
+    /// Close the queue if an FFI stream has finished.
+    ///
+    /// libxmtp signals a normal end only if no item failed before it, so a
+    /// stream that stops after an error is noticed here instead.
+    fn detect_end(&self) {
+        if self.is_closed() {
+            self.rx.finish();
+        }
+    }
+
+    /// Why the stream stopped, or `None` while it is still running.
+    #[must_use]
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        self.rx.close_reason()
+    }
+
+    /// Whether the stream (or any of the streams it merges) has finished.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.streams.iter().any(FfiStream::is_closed)
+    }
+}
+
+impl<T> Iterator for Subscription<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.recv()
+    }
+}
+
+/// Per-item errors are skipped. A stream that stops after an error is only
+/// noticed when the subscription is polled again.
+#[cfg(feature = "async")]
+impl<T> futures_core::Stream for Subscription<T> {
+    type Item = T;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.detect_end();
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        // Release a callback blocked on a full queue before the FFI streams
+        // are ended and freed.
+        self.rx.close();
+    }
+}
+
+impl<T> fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("is_closed", &self.is_closed())
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+/// A new-message event from a message stream.
+///
+/// Non-exhaustive so further stream metadata can be added without a breaking
+/// change; `sent_at_ns` was the first such addition.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MessageEvent {
+    /// Hex-encoded message ID.
+    pub message_id: String,
+    /// Hex-encoded conversation (group) ID.
+    pub conversation_id: String,
+    /// Sent timestamp in nanoseconds.
+    pub sent_at_ns: i64,
+}
+
+/// A [`Message`] together with its content, decoded with the client's
+/// [`CodecRegistry`].
+#[cfg(feature = "content")]
+#[derive(Debug, Clone)]
+pub struct DecodedMessage {
+    /// The full message.
+    pub message: Message,
+    /// Decoded content, or `None` if the payload could not be decoded
+    /// (see [`Message::fallback`]).
+    pub content: Option<Content>,
+}
+
+#[cfg(feature = "content")]
+impl DecodedMessage {
+    pub(crate) fn new(message: Message, codecs: &CodecRegistry) -> Self {
+        let content = message.decode_with(codecs).ok();
+        Self { message, content }
+    }
+}
+
+/// A consent state change event.
+#[derive(Debug, Clone)]
+pub struct ConsentUpdate {
+    /// Entity type (group ID or inbox ID).
+    pub entity_type: ConsentEntityType,
+    /// The consent state.
+    pub state: ConsentState,
+    /// The entity identifier.
+    pub entity: String,
+}
+
+/// A user preference update event.
+#[derive(Debug, Clone)]
+pub struct PreferenceUpdate {
+    /// The kind of preference change.
+    pub kind: PreferenceKind,
+    /// For Consent updates: the consent change details.
+    pub consent: Option<ConsentUpdate>,
+}
+
+/// Callback context handed to the FFI layer.
+///
+/// `repr(C)` keeps `on_event` at offset 0, so data trampolines can cast the
+/// context pointer straight to `F`.
+#[repr(C)]
+struct StreamCtx<F> {
+    on_event: F,
+    /// libxmtp's `on_close` signal: `None` when the stream ended normally, or
+    /// the error of a single failed item. It fires at most once per stream.
+    on_close: Box<dyn Fn(Option<String>) + Send>,
+}
+
+/// A running FFI stream and the callback context it reads from.
+///
+/// Dropping it stops the stream and waits for libxmtp to confirm it has
+/// finished before freeing the context. If that takes longer than
+/// [`END_TIMEOUT`], the context is leaked rather than freed under a callback
+/// that may still run.
+struct FfiStream {
+    handle: OwnedHandle<xmtp_sys::XmtpFfiStreamHandle>,
+    ctx: ManuallyDrop<CtxOwner>,
+}
+
+impl FfiStream {
+    /// Signal the stream to stop. Safe to call multiple times.
+    fn end(&self) {
+        // SAFETY: `self.handle` is a valid stream handle; safe to call multiple times.
+        unsafe { xmtp_sys::xmtp_stream_end(self.handle.as_ptr()) };
+    }
+
+    fn is_closed(&self) -> bool {
+        // SAFETY: `self.handle` is a valid stream handle.
+        unsafe { xmtp_sys::xmtp_stream_is_closed(self.handle.as_ptr()) == 1 }
+    }
+
+    /// Wait until the stream task has finished, so no callback is running.
+    /// Returns `false` if it did not finish within [`END_TIMEOUT`].
+    #[allow(
+        clippy::disallowed_methods,
+        reason = "an ended stream finishes within milliseconds; drop cannot await"
+    )]
+    fn wait_closed(&self) -> bool {
+        let deadline = Instant::now() + END_TIMEOUT;
+        while !self.is_closed() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+}
+
+impl Drop for FfiStream {
+    fn drop(&mut self) {
+        self.end();
+        if self.wait_closed() {
+            // SAFETY: libxmtp no longer calls back into the context, and
+            // `self.ctx` is not used after this.
+            unsafe { ManuallyDrop::drop(&mut self.ctx) };
+        }
+    }
+}
+
+/// Sole owner of a boxed `StreamCtx<F>`, with `F` erased.
+///
+/// libxmtp only borrows the context; it is freed exactly once, when this
+/// value is dropped.
+struct CtxOwner {
+    ptr: *mut c_void,
+    free: unsafe fn(*mut c_void),
+}
+
+// SAFETY: `ptr` is a `Box<StreamCtx<F>>` with `F: Send`, and the owner only
+// ever uses it to free the context.
+unsafe impl Send for CtxOwner {}
+
+impl CtxOwner {
+    fn new<F: Send + 'static>(ctx: StreamCtx<F>) -> Self {
+        Self {
+            ptr: Box::into_raw(Box::new(ctx)).cast(),
+            free: free_ctx::<F>,
+        }
+    }
+
+    /// The context pointer handed to the FFI layer.
+    const fn as_ptr(&self) -> *mut c_void {
+        self.ptr
+    }
+}
+
+impl Drop for CtxOwner {
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr` was created by `CtxOwner::new` with the matching `F`.
+        unsafe { (self.free)(self.ptr) };
+    }
+}
+
+/// Free a `StreamCtx<F>` created by [`CtxOwner::new`].
+///
+/// # Safety
+///
+/// `ptr` must come from [`CtxOwner::new::<F>`] and not have been freed.
+unsafe fn free_ctx<F>(ptr: *mut c_void) {
+    // SAFETY: Guaranteed by the caller.
+    drop(unsafe { Box::from_raw(ptr.cast::<StreamCtx<F>>()) });
+}
+
+/// Start an FFI stream whose callback feeds a channel.
+///
+/// The callback `F` is a pre-erased trait object (`Box<dyn Fn(…)>`) whose
+/// raw pointer is passed to the FFI trampoline. The corresponding trampoline
+/// casts the context back to the same type, reading the fat pointer correctly.
+/// A normal close from libxmtp closes the channel; a per-item error is queued
+/// as [`StreamEvent::Error`] and the stream keeps running.
+fn start_stream<T: Send + 'static, F: Send + 'static>(
+    callback: F,
+    closer: channel::Closer<T>,
+    start: impl FnOnce(*mut c_void, OnCloseFn, *mut *mut xmtp_sys::XmtpFfiStreamHandle) -> i32,
+) -> Result<FfiStream> {
+    let ctx = CtxOwner::new(StreamCtx {
+        on_event: callback,
+        on_close: Box::new(move |err| match err {
+            None => closer.close(CloseReason::Ended),
+            Some(e) => closer.error(e),
+        }),
+    });
+    let mut out: *mut xmtp_sys::XmtpFfiStreamHandle = ptr::null_mut();
+    if start(ctx.as_ptr(), Some(close_trampoline::<F>), &raw mut out) != 0 {
+        return Err(error::last_ffi_error());
+    }
+    Ok(FfiStream {
+        handle: OwnedHandle::new(out, xmtp_sys::xmtp_stream_free)?,
+        ctx: ManuallyDrop::new(ctx),
+    })
+}
+
+/// Stream new conversations.
+///
+/// Pass `None` for `conversation_type` to receive all types.
+pub fn conversations(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+) -> Result<Subscription<Conversation>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(Conversation) + Send> = Box::new(move |conv| tx.send(conv));
+    let stream = conversation_stream(client, conversation_type, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream all messages across conversations.
+///
+/// Pass `None` for `conversation_type` to receive from all types.
+pub fn messages(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+) -> Result<Subscription<MessageEvent>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(MessageEvent) + Send> = Box::new(move |event| tx.send(event));
+    let stream = all_messages_stream(client, conversation_type, consent_states, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream all messages across conversations as full [`Message`] values.
+///
+/// Each message is looked up inside the stream callback, after libxmtp has
+/// stored it, so consumers need no extra [`Client::message_by_id`] call and
+/// cannot race the database insert. The subscription keeps the client's FFI
+/// handle alive.
+pub fn enriched_messages(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+) -> Result<Subscription<Message>> {
+    filtered_messages(
+        client,
+        conversation_type,
+        consent_states,
+        &StreamFilter::default(),
+    )
+}
+
+/// Like [`enriched_messages`], but only delivers messages that pass `filter`.
+///
+/// Filtering happens in the stream callback, so rejected messages never reach
+/// the subscription's queue.
+pub fn filtered_messages(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+    filter: &StreamFilter,
+) -> Result<Subscription<Message>> {
+    let (tx, rx) = channel::channel();
+    let cb = lookup(client, filter, move |m| tx.send(m))?;
+    let stream = all_messages_stream(client, conversation_type, consent_states, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Like [`enriched_messages`], but also decodes each message's content.
+#[cfg(feature = "content")]
+pub fn decoded_messages(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+) -> Result<Subscription<DecodedMessage>> {
+    let (tx, rx) = channel::channel();
+    let codecs = Arc::clone(&client.codecs);
+    let cb = lookup(client, &StreamFilter::default(), move |m| {
+        tx.send(DecodedMessage::new(m, &codecs));
+    })?;
+    let stream = all_messages_stream(client, conversation_type, consent_states, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream messages for a single conversation.
+pub fn conversation_messages(conversation: &Conversation) -> Result<Subscription<MessageEvent>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(MessageEvent) + Send> = Box::new(move |event| tx.send(event));
+    let stream = conversation_messages_stream(conversation, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream messages for a single conversation as full [`Message`] values.
+///
+/// See [`enriched_messages`].
+pub fn conversation_enriched_messages(
+    client: &Client,
+    conversation: &Conversation,
+) -> Result<Subscription<Message>> {
+    let (tx, rx) = channel::channel();
+    let cb = lookup(client, &StreamFilter::default(), move |m| tx.send(m))?;
+    let stream = conversation_messages_stream(conversation, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Like [`conversation_enriched_messages`], but also decodes each message's content.
+#[cfg(feature = "content")]
+pub fn conversation_decoded_messages(
+    client: &Client,
+    conversation: &Conversation,
+) -> Result<Subscription<DecodedMessage>> {
+    let (tx, rx) = channel::channel();
+    let codecs = Arc::clone(&client.codecs);
+    let cb = lookup(client, &StreamFilter::default(), move |m| {
+        tx.send(DecodedMessage::new(m, &codecs));
+    })?;
+    let stream = conversation_messages_stream(conversation, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream consent state changes.
+pub fn consent(client: &Client) -> Result<Subscription<Vec<ConsentUpdate>>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(Vec<ConsentUpdate>) + Send> = Box::new(move |updates| tx.send(updates));
+    let stream = consent_stream(client, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream preference updates.
+pub fn preferences(client: &Client) -> Result<Subscription<Vec<PreferenceUpdate>>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(Vec<PreferenceUpdate>) + Send> = Box::new(move |updates| tx.send(updates));
+    let stream = preference_stream(client, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+/// Stream message deletion events. Each event yields the hex message ID.
+pub fn message_deletions(client: &Client) -> Result<Subscription<String>> {
+    let (tx, rx) = channel::channel();
+    let cb: Box<dyn Fn(String) + Send> = Box::new(move |id| tx.send(id));
+    let stream = deletion_stream(client, cb, rx.closer())?;
+    Ok(Subscription::new(rx, vec![stream]))
+}
+
+impl Client {
+    /// Subscribe to several event kinds through a single [`Subscription`].
+    ///
+    /// All selected FFI streams feed one queue, including their per-item
+    /// errors. The subscription closes when any of them closes (if it stopped
+    /// after an error, [`Subscription::close_reason`] reports it), and
+    /// dropping it stops all of them.
+    pub fn events(&self, selection: &EventSelection) -> Result<Subscription<ClientEvent>> {
+        if selection.kinds.is_empty() {
+            return Err(XmtpError::InvalidArgument("no event kinds selected".into()));
+        }
+        let (tx, rx) = channel::channel();
+        let tx = Arc::new(tx);
+        let conversation_type = selection.conversation_type;
+        let mut streams: Vec<FfiStream> = Vec::new();
+        if selection.has(EventKind::Message) {
+            let tx = Arc::clone(&tx);
+            let cb = lookup(self, &selection.message_filter, move |m| {
+                tx.send(ClientEvent::Message(Box::new(m)));
+            })?;
+            streams.push(all_messages_stream(
+                self,
+                conversation_type,
+                &selection.consent_states,
+                cb,
+                rx.closer(),
+            )?);
+        }
+        if selection.has(EventKind::Conversation) {
+            let cb = events::tag(&tx, ClientEvent::Conversation);
+            streams.push(conversation_stream(
+                self,
+                conversation_type,
+                cb,
+                rx.closer(),
+            )?);
+        }
+        if selection.has(EventKind::Consent) {
+            let cb = events::tag(&tx, ClientEvent::Consent);
+            streams.push(consent_stream(self, cb, rx.closer())?);
+        }
+        if selection.has(EventKind::Preference) {
+            let cb = events::tag(&tx, ClientEvent::Preferences);
+            streams.push(preference_stream(self, cb, rx.closer())?);
+        }
+        if selection.has(EventKind::Deletion) {
+            let cb = events::tag(&tx, ClientEvent::MessageDeleted);
+            streams.push(deletion_stream(self, cb, rx.closer())?);
+        }
+        Ok(Subscription::new(rx, streams))
+    }
+}
+
+/// Build a message callback that resolves each event to a full [`Message`]
+/// and hands it to `deliver` if it passes `filter`.
+///
+/// Events whose message cannot be read back are skipped.
+fn lookup(
+    client: &Client,
+    filter: &StreamFilter,
+    deliver: impl Fn(Message) + Send + 'static,
+) -> Result<Box<dyn Fn(MessageEvent) + Send>> {
+    let own_inbox_id = filter
+        .excludes_own()
+        .then(|| client.inbox_id())
+        .transpose()?;
+    let filter = filter.clone();
+    let handle = Arc::clone(&client.handle);
+    Ok(Box::new(move |event| {
+        if !filter.matches_conversation(&event.conversation_id) {
+            return;
+        }
+        let Ok(Some(msg)) = handle.message_by_id(&event.message_id) else {
+            return;
+        };
+        if filter.matches(&msg, own_inbox_id.as_deref()) {
+            deliver(msg);
+        }
+    }))
+}
+
+fn conversation_stream<T: Send + 'static>(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    cb: Box<dyn Fn(Conversation) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let client_ptr = client.handle.as_ptr();
+    let conv_type = conversation_type.map_or(-1, |t| t as i32);
+    // SAFETY: Valid client pointer and callback context; `out` receives the stream handle.
+    start_stream(cb, closer, |ctx, on_close, out| unsafe {
+        xmtp_sys::xmtp_stream_conversations(
+            client_ptr,
+            conv_type,
+            Some(conv_trampoline),
+            on_close,
+            ctx,
+            out,
+        )
+    })
+}
+
+fn all_messages_stream<T: Send + 'static>(
+    client: &Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+    cb: Box<dyn Fn(MessageEvent) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let client_ptr = client.handle.as_ptr();
+    let conv_type = conversation_type.map_or(-1, |t| t as i32);
+    let cs: Vec<i32> = consent_states.iter().map(|s| *s as i32).collect();
+    let cs_ptr = if cs.is_empty() {
+        ptr::null()
+    } else {
+        cs.as_ptr()
+    };
+    let cs_len = to_ffi_len(cs.len())?;
+    // SAFETY: Valid client pointer, consent arrays, and callback context.
+    start_stream(cb, closer, |ctx, on_close, out| unsafe {
+        xmtp_sys::xmtp_stream_all_messages(
+            client_ptr,
+            conv_type,
+            cs_ptr,
+            cs_len,
+            Some(msg_trampoline),
+            on_close,
+            ctx,
+            out,
+        )
+    })
+}
+
+fn conversation_messages_stream<T: Send + 'static>(
+    conversation: &Conversation,
+    cb: Box<dyn Fn(MessageEvent) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let conv_ptr = conversation.handle_ptr();
+    // SAFETY: Valid conversation pointer and callback context.
+    start_stream(cb, closer, |ctx, on_close, out| unsafe {
+        xmtp_sys::xmtp_conversation_stream_messages(
+            conv_ptr,
+            Some(msg_trampoline),
+            on_close,
+            ctx,
+            out,
+        )
+    })
+}
+
+fn consent_stream<T: Send + 'static>(
+    client: &Client,
+    cb: Box<dyn Fn(Vec<ConsentUpdate>) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let client_ptr = client.handle.as_ptr();
+    // SAFETY: Valid client pointer and callback context.
+    start_stream(cb, closer, |ctx, on_close, out| unsafe {
+        xmtp_sys::xmtp_stream_consent(client_ptr, Some(consent_trampoline), on_close, ctx, out)
+    })
+}
+
+fn preference_stream<T: Send + 'static>(
+    client: &Client,
+    cb: Box<dyn Fn(Vec<PreferenceUpdate>) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let client_ptr = client.handle.as_ptr();
+    // SAFETY: Valid client pointer and callback context.
+    start_stream(cb, closer, |ctx, on_close, out| unsafe {
+        xmtp_sys::xmtp_stream_preferences(client_ptr, Some(pref_trampoline), on_close, ctx, out)
+    })
+}
+
+fn deletion_stream<T: Send + 'static>(
+    client: &Client,
+    cb: Box<dyn Fn(String) + Send>,
+    closer: channel::Closer<T>,
+) -> Result<FfiStream> {
+    let client_ptr = client.handle.as_ptr();
+    // libxmtp fires this stream's `on_close` from a detached task that can
+    // outlive `xmtp_stream_free`, so none is passed; the subscription notices
+    // the end by polling instead.
+    // SAFETY: Valid client pointer and callback context.
+    start_stream(cb, closer, |ctx, _, out| unsafe {
+        xmtp_sys::xmtp_stream_message_deletions(
+            client_ptr,
+            Some(deletion_trampoline),
+            None,
+            ctx,
+            out,
+        )
+    })
+}
+
+unsafe extern "C" fn conv_trampoline(
+    conv: *mut xmtp_sys::XmtpFfiConversation,
+    context: *mut c_void,
+) {
+    if context.is_null() || conv.is_null() {
+        return;
+    }
+    // SAFETY: `context` is a `StreamCtx<Box<dyn Fn(Conversation) + Send>>` created by `subscribe`.
+    let cb = unsafe { &*context.cast::<Box<dyn Fn(Conversation) + Send>>() };
+    if let Ok(c) = Conversation::from_raw(conv) {
+        cb(c);
+    }
+}
+
+unsafe extern "C" fn msg_trampoline(msg: *mut xmtp_sys::XmtpFfiMessage, context: *mut c_void) {
+    if context.is_null() || msg.is_null() {
+        if !msg.is_null() {
+            // SAFETY: `msg` is an FFI-allocated message that must be freed.
+            unsafe { xmtp_sys::xmtp_message_free(msg) };
+        }
+        return;
+    }
+    // SAFETY: Extract message ID before freeing.
+    let id_ptr = unsafe { xmtp_sys::xmtp_single_message_id(msg) };
+    // SAFETY: Extract group ID before freeing.
+    let gid_ptr = unsafe { xmtp_sys::xmtp_single_message_group_id(msg) };
+    // SAFETY: `msg` is a valid FFI message handle.
+    let sent_at_ns = unsafe { xmtp_sys::xmtp_single_message_sent_at_ns(msg) };
+    // SAFETY: `msg` is an FFI-allocated message that must be freed.
+    unsafe { xmtp_sys::xmtp_message_free(msg) };
+
+    // SAFETY: `context` is a `StreamCtx<Box<dyn Fn(MessageEvent) + Send>>` created by `subscribe`.
+    let cb = unsafe { &*context.cast::<Box<dyn Fn(MessageEvent) + Send>>() };
+    let id = if id_ptr.is_null() {
+        String::new()
+    } else {
+        // SAFETY: `id_ptr` is a valid NUL-terminated C string.
+        let s = unsafe { CStr::from_ptr(id_ptr) }
+            .to_str()
+            .unwrap_or_default()
+            .to_owned();
+        // SAFETY: `id_ptr` was allocated by the FFI layer.
+        unsafe { xmtp_sys::xmtp_free_string(id_ptr) };
+        s
+    };
+    let gid = if gid_ptr.is_null() {
+        String::new()
+    } else {
+        // SAFETY: `gid_ptr` is a valid NUL-terminated C string.
+        let s = unsafe { CStr::from_ptr(gid_ptr) }
+            .to_str()
+            .unwrap_or_default()
+            .to_owned();
+        // SAFETY: `gid_ptr` was allocated by the FFI layer.
+        unsafe { xmtp_sys::xmtp_free_string(gid_ptr) };
+        s
+    };
+    cb(MessageEvent {
+        message_id: id,
+        conversation_id: gid,
+        sent_at_ns,
+    });
+}
+
+unsafe extern "C" fn close_trampoline<F>(err: *const c_char, context: *mut c_void) {
+    if context.is_null() {
+        return;
+    }
+    // SAFETY: `context` is a `StreamCtx<F>` owned by the `FfiStream`, which
+    // outlives every callback. libxmtp may keep streaming after an error, so
+    // the context is only borrowed here.
+    let ctx = unsafe { &*context.cast::<StreamCtx<F>>() };
+    let err = (!err.is_null()).then(|| {
+        // SAFETY: `err` is a borrowed NUL-terminated C string valid for this call.
+        unsafe { CStr::from_ptr(err) }
+            .to_string_lossy()
+            .into_owned()
+    });
+    (ctx.on_close)(err);
+}
+
+unsafe extern "C" fn consent_trampoline(
+    records: *const xmtp_sys::XmtpFfiConsentRecord,
+    count: i32,
+    context: *mut c_void,
+) {
+    if context.is_null() || records.is_null() || count <= 0 {
+        return;
+    }
+    // SAFETY: `context` is a `StreamCtx<Box<dyn Fn(Vec<ConsentUpdate>) + Send>>` created by `subscribe`.
+    let cb = unsafe { &*context.cast::<Box<dyn Fn(Vec<ConsentUpdate>) + Send>>() };
+    // SAFETY: `records` points to `count` valid consent records.
+    let slice = unsafe { std::slice::from_raw_parts(records, count.unsigned_abs() as usize) };
+    let updates: Vec<ConsentUpdate> = slice
+        .iter()
+        .filter_map(|r| {
+            let entity_type = ConsentEntityType::from_ffi(r.entity_type as i32)?;
+            let state = ConsentState::from_ffi(r.state as i32)?;
+            // SAFETY: `r.entity` is a valid NUL-terminated C string.
+            let entity = unsafe { CStr::from_ptr(r.entity) }
+                .to_str()
+                .ok()?
+                .to_owned();
+            Some(ConsentUpdate {
+                entity_type,
+                state,
+                entity,
+            })
+        })
+        .collect();
+    if !updates.is_empty() {
+        cb(updates);
+    }
+}
+
+unsafe extern "C" fn pref_trampoline(
+    updates: *const xmtp_sys::XmtpFfiPreferenceUpdate,
+    count: i32,
+    context: *mut c_void,
+) {
+    if context.is_null() || updates.is_null() || count <= 0 {
+        return;
+    }
+    // SAFETY: `context` is a `StreamCtx<Box<dyn Fn(Vec<PreferenceUpdate>) + Send>>` created by `subscribe`.
+    let cb = unsafe { &*context.cast::<Box<dyn Fn(Vec<PreferenceUpdate>) + Send>>() };
+    // SAFETY: `updates` points to `count` valid preference records.
+    let slice = unsafe { std::slice::from_raw_parts(updates, count.unsigned_abs() as usize) };
+    let items: Vec<PreferenceUpdate> = slice
+        .iter()
+        .filter_map(|u| {
+            let kind = PreferenceKind::from_ffi(u.kind as i32)?;
+            let consent = if kind == PreferenceKind::Consent {
+                let r = &u.consent;
+                let et = ConsentEntityType::from_ffi(r.entity_type as i32);
+                let st = ConsentState::from_ffi(r.state as i32);
+                let entity = if r.entity.is_null() {
+                    String::new()
+                } else {
+                    // SAFETY: `r.entity` is a valid NUL-terminated C string.
+                    unsafe { CStr::from_ptr(r.entity) }
+                        .to_str()
+                        .unwrap_or_default()
+                        .to_owned()
+                };
+                et.zip(st).map(|(entity_type, state)| ConsentUpdate {
+                    entity_type,
+                    state,
+                    entity,
+                })
+            } else {
+                None
+            };
+            Some(PreferenceUpdate { kind, consent })
+        })
+        .collect();
+    if !items.is_empty() {
+        cb(items);
+    }
+}
+
+unsafe extern "C" fn deletion_trampoline(message_id: *const c_char, context: *mut c_void) {
+    if context.is_null() || message_id.is_null() {
+        return;
+    }
+    // SAFETY: `context` is a `StreamCtx<Box<dyn Fn(String) + Send>>` created by `subscribe`.
+    let cb = unsafe { &*context.cast::<Box<dyn Fn(String) + Send>>() };
+    // SAFETY: `message_id` is a valid NUL-terminated C string.
+    if let Ok(id) = unsafe { CStr::from_ptr(message_id) }.to_str() {
+        cb(id.to_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Counts how often it is dropped.
+    struct DropCount(Arc<AtomicUsize>);
+
+    impl Drop for DropCount {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn item_error_keeps_stream_running_and_context_is_freed_once() {
+        type Cb = Box<dyn Fn(String) + Send>;
+        let (tx, rx) = channel::channel::<String>();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let count = DropCount(Arc::clone(&drops));
+        let cb: Cb = Box::new(move |id| {
+            let _ = &count;
+            tx.send(id);
+        });
+        let closer = rx.closer();
+        let ctx = CtxOwner::new(StreamCtx {
+            on_event: cb,
+            on_close: Box::new(move |err| match err {
+                None => closer.close(CloseReason::Ended),
+                Some(e) => closer.error(e),
+            }),
+        });
+        let (a, boom, b) = (c"a", CString::new("boom").unwrap(), c"b");
+
+        // SAFETY: `ctx` is a live `StreamCtx<Cb>`; `a` outlives the call.
+        unsafe { deletion_trampoline(a.as_ptr(), ctx.as_ptr()) };
+        // SAFETY: As above; libxmtp reports a per-item error.
+        unsafe { close_trampoline::<Cb>(boom.as_ptr(), ctx.as_ptr()) };
+        // SAFETY: As above; the stream keeps running after the error.
+        unsafe { deletion_trampoline(b.as_ptr(), ctx.as_ptr()) };
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(ctx);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+
+        let next = || rx.recv_event_timeout(Duration::ZERO);
+        assert_eq!(next(), Some(StreamEvent::Item("a".into())));
+        assert_eq!(next(), Some(StreamEvent::Error("boom".into())));
+        assert_eq!(next(), Some(StreamEvent::Item("b".into())));
+        assert_eq!(next(), Some(StreamEvent::Closed(CloseReason::Ended)));
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,429 @@
+//! Self-healing message stream that reconnects after errors and replays the gap.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::time::Duration;
+
+use super::{CloseReason, MessageEvent, Overflow, StreamEvent, Subscription, messages};
+use crate::client::Client;
+use crate::error::Result;
+use crate::types::{
+    ConsentState, ConversationOrderBy, ConversationType, ListConversationsOptions,
+    ListMessagesOptions, SortDirection,
+};
+
+/// Exponential reconnection backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay before the first reconnection attempt.
+    pub initial: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max: Duration,
+    /// Give up after this many consecutive failed attempts. `None` = never.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before attempt number `attempt` (0-based): `initial * 2^attempt`, capped at `max`.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .map_or(self.max, |d| d.min(self.max))
+    }
+}
+
+/// A message stream that survives network errors.
+///
+/// When the underlying [`Subscription`] closes with
+/// [`CloseReason::Error`], the stream is re-opened with [`Backoff`], the
+/// client is synced, and every message sent since the last delivered event is
+/// replayed in order, so no messages are missed across the gap. A normal close
+/// ends iteration. Errors for single items ([`StreamEvent::Error`]) are skipped
+/// without reconnecting, since the stream keeps running after them.
+///
+/// Reconnection happens inside [`recv`](Self::recv), on the consumer's thread.
+#[derive(Debug)]
+pub struct ResilientSubscription<'a> {
+    client: &'a Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: Vec<ConsentState>,
+    backoff: Backoff,
+    bound: Option<(usize, Overflow)>,
+    dropped_before: u64,
+    sub: Option<Subscription<MessageEvent>>,
+    replay: Replay,
+    reconnects: u32,
+    close_reason: Option<CloseReason>,
+}
+
+/// Stream all messages like [`messages`], reconnecting automatically on errors.
+pub fn resilient_messages<'a>(
+    client: &'a Client,
+    conversation_type: Option<ConversationType>,
+    consent_states: &[ConsentState],
+    backoff: Backoff,
+) -> Result<ResilientSubscription<'a>> {
+    // Seed the catch-up cursor from the newest stored message, which carries
+    // a server timestamp, rather than from the local clock.
+    let newest = client.list_conversation_items(&ListConversationsOptions {
+        conversation_type,
+        consent_states: consent_states.to_vec(),
+        order_by: ConversationOrderBy::LastActivity,
+        limit: 1,
+        ..Default::default()
+    })?;
+    let last_seen_ns = newest.first().map_or(0, |i| i.last_message_sent_at_ns);
+    let sub = messages(client, conversation_type, consent_states)?;
+    Ok(ResilientSubscription {
+        client,
+        conversation_type,
+        consent_states: consent_states.to_vec(),
+        backoff,
+        bound: None,
+        dropped_before: 0,
+        sub: Some(sub),
+        replay: Replay::new(last_seen_ns),
+        reconnects: 0,
+        close_reason: None,
+    })
+}
+
+impl ResilientSubscription<'_> {
+    /// Block until the next message, reconnecting as needed.
+    ///
+    /// Returns `None` once the stream closed normally or reconnection gave up;
+    /// see [`close_reason`](Self::close_reason).
+    pub fn recv(&mut self) -> Option<MessageEvent> {
+        loop {
+            if let Some(event) = self.replay.next_pending() {
+                return Some(event);
+            }
+            match self.replay.step(self.sub.as_ref()?.recv_event()) {
+                Step::Deliver(event) => return Some(event),
+                Step::Skip => {}
+                Step::Reconnect(err) => self.reconnect(err),
+                Step::Stop(reason) => self.stop(reason),
+            }
+        }
+    }
+
+    /// End iteration after the stream closed normally.
+    fn stop(&mut self, reason: CloseReason) {
+        self.sub = None;
+        self.close_reason = Some(reason);
+    }
+
+    /// Bound every underlying subscription, including re-opened ones.
+    ///
+    /// See [`Subscription::bounded`].
+    #[must_use]
+    pub fn bounded(mut self, capacity: usize, overflow: Overflow) -> Self {
+        self.bound = Some((capacity, overflow));
+        self.sub = self.sub.map(|sub| sub.bounded(capacity, overflow));
+        self
+    }
+
+    /// Number of events discarded by the [`Overflow`] policy, across reconnects.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped_before + self.sub.as_ref().map_or(0, Subscription::dropped)
+    }
+
+    /// Stop the stream. Queued events are still delivered.
+    pub fn close(&self) {
+        if let Some(sub) = &self.sub {
+            sub.close();
+        }
+    }
+
+    /// Number of successful reconnections so far.
+    #[must_use]
+    pub const fn reconnects(&self) -> u32 {
+        self.reconnects
+    }
+
+    /// Why the stream ended, or `None` while it is still running.
+    #[must_use]
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        self.close_reason.clone()
+    }
+
+    /// Re-open the stream, retrying with backoff, then queue the missed messages.
+    fn reconnect(&mut self, err: String) {
+        self.dropped_before += self.sub.take().map_or(0, |sub| sub.dropped());
+        // Open the new stream before catching up so nothing falls in between.
+        let opened = retry(&self.backoff, err, || {
+            self.open()
+                .and_then(|sub| self.catch_up().map(|missed| (sub, missed)))
+        });
+        match opened {
+            Ok((sub, missed)) => {
+                self.replay.queue(missed);
+                self.sub = Some(sub);
+                self.reconnects += 1;
+            }
+            Err(last) => self.close_reason = Some(CloseReason::Error(last)),
+        }
+    }
+
+    /// Open a new underlying stream with the configured bound.
+    fn open(&self) -> Result<Subscription<MessageEvent>> {
+        let sub = messages(self.client, self.conversation_type, &self.consent_states)?;
+        Ok(match self.bound {
+            Some((capacity, overflow)) => sub.bounded(capacity, overflow),
+            None => sub,
+        })
+    }
+
+    /// Sync and collect messages sent after the last delivered event, oldest first.
+    fn catch_up(&self) -> Result<Vec<MessageEvent>> {
+        self.client.sync_all(&self.consent_states)?;
+        let convs = self.client.list_conversations(&ListConversationsOptions {
+            conversation_type: self.conversation_type,
+            consent_states: self.consent_states.clone(),
+            last_activity_after_ns: self.replay.last_seen_ns,
+            ..Default::default()
+        })?;
+        let opts = ListMessagesOptions {
+            sent_after_ns: self.replay.last_seen_ns,
+            direction: Some(SortDirection::Ascending),
+            ..Default::default()
+        };
+        let mut missed = Vec::new();
+        for conv in &convs {
+            missed.extend(
+                conv.list_messages(&opts)?
+                    .into_iter()
+                    .map(|m| MessageEvent {
+                        message_id: m.id,
+                        conversation_id: m.conversation_id,
+                        sent_at_ns: m.sent_at_ns,
+                    }),
+            );
+        }
+        missed.sort_by_key(|e| e.sent_at_ns);
+        Ok(missed)
+    }
+}
+
+impl Iterator for ResilientSubscription<'_> {
+    type Item = MessageEvent;
+    fn next(&mut self) -> Option<MessageEvent> {
+        self.recv()
+    }
+}
+
+/// What [`ResilientSubscription::recv`] does with one event of the underlying
+/// stream.
+#[derive(Debug)]
+enum Step {
+    Deliver(MessageEvent),
+    Skip,
+    Reconnect(String),
+    Stop(CloseReason),
+}
+
+/// Messages replayed after a reconnect, and the cursor they are fetched from.
+#[derive(Debug, Default)]
+struct Replay {
+    pending: VecDeque<MessageEvent>,
+    replayed: BTreeSet<String>,
+    last_seen_ns: i64,
+}
+
+impl Replay {
+    fn new(last_seen_ns: i64) -> Self {
+        Self {
+            last_seen_ns,
+            ..Self::default()
+        }
+    }
+
+    /// Queue messages found by a catch-up. Live copies of them that the new
+    /// stream delivers afterwards are skipped.
+    fn queue(&mut self, missed: Vec<MessageEvent>) {
+        self.replayed = missed.iter().map(|e| e.message_id.clone()).collect();
+        self.pending.extend(missed);
+    }
+
+    /// The next queued catch-up message, if any.
+    fn next_pending(&mut self) -> Option<MessageEvent> {
+        let event = self.pending.pop_front()?;
+        Some(self.deliver(event))
+    }
+
+    /// Decide how to handle an event of the underlying stream.
+    fn step(&mut self, event: StreamEvent<MessageEvent>) -> Step {
+        match event {
+            StreamEvent::Item(event) => self.live(event).map_or(Step::Skip, Step::Deliver),
+            // One item failed; the stream itself is still running.
+            StreamEvent::Error(_) => Step::Skip,
+            StreamEvent::Closed(CloseReason::Error(err)) => Step::Reconnect(err),
+            StreamEvent::Closed(reason) => Step::Stop(reason),
+        }
+    }
+
+    /// Pass a live event through unless it was already replayed.
+    fn live(&mut self, event: MessageEvent) -> Option<MessageEvent> {
+        if self.replayed.remove(&event.message_id) {
+            return None;
+        }
+        Some(self.deliver(event))
+    }
+
+    fn deliver(&mut self, event: MessageEvent) -> MessageEvent {
+        self.last_seen_ns = self.last_seen_ns.max(event.sent_at_ns);
+        event
+    }
+}
+
+/// Run `attempt` until it succeeds, sleeping per `backoff` before each try.
+///
+/// Returns the last error once `backoff.max_retries` attempts have failed.
+#[allow(
+    clippy::disallowed_methods,
+    reason = "reconnection runs on the consumer's blocking thread by design"
+)]
+fn retry<T>(
+    backoff: &Backoff,
+    mut err: String,
+    mut attempt: impl FnMut() -> Result<T>,
+) -> std::result::Result<T, String> {
+    let mut n = 0;
+    loop {
+        if backoff.max_retries.is_some_and(|max| n >= max) {
+            return Err(err);
+        }
+        std::thread::sleep(backoff.delay(n));
+        n += 1;
+        match attempt() {
+            Ok(v) => return Ok(v),
+            Err(e) => err = e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::XmtpError;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let b = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            max_retries: None,
+        };
+        assert_eq!(b.delay(0), Duration::from_millis(100));
+        assert_eq!(b.delay(3), Duration::from_millis(800));
+        assert_eq!(b.delay(4), Duration::from_secs(1));
+        assert_eq!(b.delay(64), Duration::from_secs(1));
+    }
+
+    fn event(id: &str, sent_at_ns: i64) -> MessageEvent {
+        MessageEvent {
+            message_id: id.into(),
+            conversation_id: "c".into(),
+            sent_at_ns,
+        }
+    }
+
+    #[test]
+    fn catch_up_is_replayed_first_and_live_duplicates_skipped() {
+        let mut replay = Replay::new(10);
+        assert_eq!(
+            replay.live(event("a", 5)).map(|e| e.message_id),
+            Some("a".into())
+        );
+        assert_eq!(replay.last_seen_ns, 10);
+
+        replay.queue(vec![event("b", 20), event("c", 30)]);
+        assert_eq!(
+            replay.next_pending().map(|e| e.message_id),
+            Some("b".into())
+        );
+        assert_eq!(
+            replay.next_pending().map(|e| e.message_id),
+            Some("c".into())
+        );
+        assert!(replay.next_pending().is_none());
+        assert_eq!(replay.last_seen_ns, 30);
+
+        // The re-opened stream delivers `c` again, then something new.
+        assert!(replay.live(event("c", 30)).is_none());
+        assert_eq!(
+            replay.live(event("d", 40)).map(|e| e.message_id),
+            Some("d".into())
+        );
+        assert_eq!(replay.last_seen_ns, 40);
+    }
+
+    #[test]
+    fn only_a_stream_that_ended_with_an_error_reconnects() {
+        let mut replay = Replay::new(0);
+        assert!(matches!(
+            replay.step(StreamEvent::Item(event("a", 1))),
+            Step::Deliver(e) if e.message_id == "a"
+        ));
+        assert!(matches!(
+            replay.step(StreamEvent::Error("decode failed".into())),
+            Step::Skip
+        ));
+        assert!(matches!(
+            replay.step(StreamEvent::Item(event("b", 2))),
+            Step::Deliver(e) if e.message_id == "b"
+        ));
+        assert!(matches!(
+            replay.step(StreamEvent::Closed(CloseReason::Error("network".into()))),
+            Step::Reconnect(e) if e == "network"
+        ));
+        assert!(matches!(
+            replay.step(StreamEvent::Closed(CloseReason::Ended)),
+            Step::Stop(CloseReason::Ended)
+        ));
+    }
+
+    #[test]
+    fn retry_until_success_or_limit() {
+        let fast = Backoff {
+            initial: Duration::ZERO,
+            max: Duration::ZERO,
+            max_retries: Some(3),
+        };
+        let mut calls = 0;
+        let got = retry(&fast, "initial".into(), || {
+            calls += 1;
+            if calls < 3 {
+                Err(XmtpError::Ffi(format!("attempt {calls}")))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(got, Ok(3));
+
+        let failed = retry(&fast, "initial".into(), || {
+            Err::<(), _>(XmtpError::Ffi("down".into()))
+        });
+        assert_eq!(failed, Err("xmtp ffi: down".into()));
+
+        let none = Backoff {
+            max_retries: Some(0),
+            ..fast
+        };
+        assert_eq!(
+            retry(&none, "initial".into(), || Ok(())),
+            Err("initial".into())
+        );
+    }
+}
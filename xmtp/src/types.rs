@@ -222,6 +222,37 @@ pub enum PreferenceKind {
     }
 }
 
+ffi_enum! {
+    /// Element selection for device sync archives (FFI bitmask bits).
+    pub enum ArchiveElement {
+        /// Conversation messages.
+        Messages = 1,
+        /// Consent records.
+        Consent = 2,
+        /// Event records. Only reported in [`ArchiveMetadata`]: libxmtp
+        /// ignores this bit when exporting, so it has no effect in
+        /// [`ArchiveOptions`].
+        Event = 4,
+    }
+}
+
+impl ArchiveElement {
+    /// Fold a set of elements into the FFI bitmask.
+    #[must_use]
+    pub fn to_bits(elements: &[Self]) -> i32 {
+        elements.iter().fold(0, |bits, e| bits | *e as i32)
+    }
+
+    /// Expand an FFI bitmask into elements. Unknown bits are ignored.
+    #[must_use]
+    pub fn from_bits(bits: i32) -> Vec<Self> {
+        [Self::Messages, Self::Consent, Self::Event]
+            .into_iter()
+            .filter(|e| bits & *e as i32 != 0)
+            .collect()
+    }
+}
+
 /// Metadata field names for [`PermissionUpdateType::UpdateMetadata`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MetadataField {
@@ -363,6 +394,54 @@ fn default() -> Self {
     }
 }
 
+/// Options for creating or requesting a device sync archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveOptions {
+    /// Elements to include (default: messages and consent).
+    pub elements: Vec<ArchiveElement>,
+    /// Only records after this timestamp (ns). 0 = no filter.
+    pub start_ns: i64,
+    /// Only records before this timestamp (ns). 0 = no filter.
+    pub end_ns: i64,
+    /// Whether to exclude disappearing messages.
+    pub exclude_disappearing: bool,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            elements: vec![ArchiveElement::Messages, ArchiveElement::Consent],
+            start_ns: 0,
+            end_ns: 0,
+            exclude_disappearing: false,
+        }
+    }
+}
+
+/// Metadata read from a device sync archive file.
+#[derive(Debug, Clone)]
+pub struct ArchiveMetadata {
+    /// Archive format version.
+    pub version: u16,
+    /// Export timestamp in nanoseconds.
+    pub exported_at_ns: i64,
+    /// Elements contained in the archive.
+    pub elements: Vec<ArchiveElement>,
+    /// Start of the exported range (ns), if filtered.
+    pub start_ns: Option<i64>,
+    /// End of the exported range (ns), if filtered.
+    pub end_ns: Option<i64>,
+}
+
+/// An archive offered by another installation in the device sync group.
+#[derive(Debug, Clone)]
+pub struct AvailableArchive {
+    /// PIN identifying the archive.
+    pub pin: String,
+    /// Export timestamp in nanoseconds.
+    pub exported_at_ns: i64,
+}
+
 /// Disappearing message settings.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct DisappearingSettings {
@@ -545,6 +624,19 @@ pub struct InboxState {
     pub installation_ids: Vec<String>,
 }
 
+/// A passkey (WebAuthn) assertion over an identity signature text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PasskeySignature {
+    /// SEC1-encoded P-256 public key of the credential.
+    pub public_key: Vec<u8>,
+    /// DER-encoded ECDSA signature over `authenticator_data || sha256(client_data_json)`.
+    pub signature: Vec<u8>,
+    /// Raw WebAuthn authenticator data.
+    pub authenticator_data: Vec<u8>,
+    /// Raw WebAuthn client data JSON (its `challenge` encodes the signature text).
+    pub client_data_json: Vec<u8>,
+}
+
 /// Trait for signing messages during XMTP identity operations.
 pub trait Signer: Send + Sync {
     /// The account identifier for this signer.
@@ -571,6 +663,17 @@ fn chain_id(&self) -> u64 {
     fn block_number(&self) -> u64 {
         0
     }
+
+    /// Produce a passkey assertion for `text`.
+    ///
+    /// Called instead of [`sign`](Self::sign) when [`identifier`](Self::identifier)
+    /// has kind [`IdentifierKind::Passkey`]. The default returns an error.
+    fn sign_passkey(&self, text: &str) -> crate::error::Result<PasskeySignature> {
+        let _ = text;
+        Err(crate::XmtpError::Signing(
+            "signer does not support passkey signatures".into(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -653,6 +756,44 @@ fn ffi_discriminants_message_and_preference() {
         assert_eq!(PreferenceKind::from_ffi(2), None);
     }
 
+    /// `ArchiveElement` bitmask round-trip.
+    #[test]
+    fn archive_element_bitmask() {
+        assert_eq!(ArchiveElement::from_ffi(1), Some(ArchiveElement::Messages));
+        assert_eq!(ArchiveElement::from_ffi(2), Some(ArchiveElement::Consent));
+        assert_eq!(ArchiveElement::from_ffi(4), Some(ArchiveElement::Event));
+        assert_eq!(ArchiveElement::from_ffi(3), None);
+
+        assert_eq!(ArchiveElement::to_bits(&[]), 0);
+        assert_eq!(
+            ArchiveElement::to_bits(&ArchiveOptions::default().elements),
+            3
+        );
+        assert_eq!(ArchiveElement::from_bits(2), vec![ArchiveElement::Consent]);
+        assert_eq!(
+            ArchiveElement::from_bits(1 | 2 | 4),
+            vec![
+                ArchiveElement::Messages,
+                ArchiveElement::Consent,
+                ArchiveElement::Event
+            ]
+        );
+
+        // Every known combination survives a round trip; unknown bits are dropped.
+        for known in 0..8 {
+            assert_eq!(
+                ArchiveElement::to_bits(&ArchiveElement::from_bits(known)),
+                known
+            );
+            for unknown in [8, 16, 1 << 30, i32::MIN] {
+                assert_eq!(
+                    ArchiveElement::to_bits(&ArchiveElement::from_bits(known | unknown)),
+                    known
+                );
+            }
+        }
+    }
+
     /// `PermissionPolicy` discriminants + the `+1` write-offset logic.
     #[test]
     fn permission_policy_discriminants_and_write_offset() {